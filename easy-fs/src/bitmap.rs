@@ -0,0 +1,94 @@
+//! Block-level bitmap allocator backing easy-fs's free-inode and
+//! free-data-block lists: one bit per id, `1` meaning allocated.
+
+use super::{get_block_cache, BlockDevice, BLOCK_SZ};
+use alloc::sync::Arc;
+
+/// Bits tracked per bitmap block.
+const BLOCK_BITS: usize = BLOCK_SZ * 8;
+
+type BitmapBlock = [u64; BLOCK_BITS / 64];
+
+/// A bitmap spanning `blocks` on-disk blocks starting at `start_block_id`.
+pub struct Bitmap {
+    start_block_id: usize,
+    blocks: usize,
+}
+
+impl Bitmap {
+    /// A bitmap covering `blocks` blocks of bits, stored starting at
+    /// `start_block_id`.
+    pub fn new(start_block_id: usize, blocks: usize) -> Self {
+        Self {
+            start_block_id,
+            blocks,
+        }
+    }
+
+    /// Find and set the first clear bit, returning its position (0-indexed
+    /// across the whole bitmap), or `None` if every bit is set.
+    pub fn alloc(&self, block_device: &Arc<dyn BlockDevice>) -> Option<usize> {
+        for block_id in 0..self.blocks {
+            let pos = get_block_cache(block_id + self.start_block_id, Arc::clone(block_device))
+                .lock()
+                .modify(0, |bitmap_block: &mut BitmapBlock| {
+                    bitmap_block
+                        .iter()
+                        .enumerate()
+                        .find(|(_, bits64)| **bits64 != u64::MAX)
+                        .map(|(words_pos, bits64)| (words_pos, bits64.trailing_ones() as usize))
+                        .map(|(words_pos, inner_pos)| {
+                            bitmap_block[words_pos] |= 1u64 << inner_pos;
+                            words_pos * 64 + inner_pos
+                        })
+                });
+            if let Some(inner) = pos {
+                return Some(block_id * BLOCK_BITS + inner);
+            }
+        }
+        None
+    }
+
+    /// Clear the bit at `bit` (as returned by `alloc`).
+    pub fn dealloc(&self, block_device: &Arc<dyn BlockDevice>, bit: usize) {
+        let (block_id, words_pos, inner_pos) = Self::decompose(bit);
+        get_block_cache(block_id + self.start_block_id, Arc::clone(block_device))
+            .lock()
+            .modify(0, |bitmap_block: &mut BitmapBlock| {
+                assert!(bitmap_block[words_pos] & (1u64 << inner_pos) != 0);
+                bitmap_block[words_pos] &= !(1u64 << inner_pos);
+            });
+    }
+
+    /// Maximum number of ids this bitmap has room to track.
+    pub(crate) fn maximum(&self) -> usize {
+        self.blocks * BLOCK_BITS
+    }
+
+    /// Same count as `maximum`, exposed for `statfs`'s `*_total` fields.
+    pub(crate) fn total_bits(&self) -> usize {
+        self.maximum()
+    }
+
+    /// Count of ids not currently allocated.
+    pub(crate) fn count_free(&self, block_device: &Arc<dyn BlockDevice>) -> usize {
+        let set = (0..self.blocks)
+            .map(|block_id| {
+                get_block_cache(block_id + self.start_block_id, Arc::clone(block_device))
+                    .lock()
+                    .read(0, |bitmap_block: &BitmapBlock| {
+                        bitmap_block.iter().map(|bits64| bits64.count_ones() as usize).sum::<usize>()
+                    })
+            })
+            .sum::<usize>();
+        self.total_bits() - set
+    }
+
+    /// Split a bitmap-wide bit position into its block, 64-bit-word, and
+    /// in-word bit indices.
+    fn decompose(bit: usize) -> (usize, usize, usize) {
+        let block_id = bit / BLOCK_BITS;
+        let bit_in_block = bit % BLOCK_BITS;
+        (block_id, bit_in_block / 64, bit_in_block % 64)
+    }
+}
@@ -0,0 +1,141 @@
+//! Block cache: every on-disk read/write in this crate goes through a
+//! cached copy here rather than hitting `BlockDevice` directly each time,
+//! and writes only reach the device on `sync`/eviction/drop.
+
+use super::BlockDevice;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use lazy_static::lazy_static;
+use spin::Mutex;
+
+/// Bytes per block, matching the on-disk layout the rest of this crate
+/// assumes.
+pub const BLOCK_SZ: usize = 512;
+
+/// How many blocks stay cached before the least-recently-fetched unreferenced
+/// one gets evicted.
+const BLOCK_CACHE_SIZE: usize = 16;
+
+/// One cached block: its bytes, which block/device it came from, and
+/// whether it's been written since the last `sync`.
+pub struct BlockCache {
+    cache: [u8; BLOCK_SZ],
+    block_id: usize,
+    block_device: Arc<dyn BlockDevice>,
+    modified: bool,
+}
+
+impl BlockCache {
+    /// Load `block_id` off `block_device` into a fresh cache entry.
+    pub fn new(block_id: usize, block_device: Arc<dyn BlockDevice>) -> Self {
+        let mut cache = [0u8; BLOCK_SZ];
+        block_device.read_block(block_id, &mut cache);
+        Self {
+            cache,
+            block_id,
+            block_device,
+            modified: false,
+        }
+    }
+
+    fn addr_of_offset(&self, offset: usize) -> usize {
+        &self.cache[offset] as *const _ as usize
+    }
+
+    /// Borrow the value of type `T` living at `offset` into this block.
+    pub fn get_ref<T: Sized>(&self, offset: usize) -> &T {
+        let type_size = core::mem::size_of::<T>();
+        assert!(offset + type_size <= BLOCK_SZ);
+        unsafe { &*(self.addr_of_offset(offset) as *const T) }
+    }
+
+    /// Mutably borrow the value of type `T` living at `offset`, marking this
+    /// block dirty.
+    pub fn get_mut<T: Sized>(&mut self, offset: usize) -> &mut T {
+        let type_size = core::mem::size_of::<T>();
+        assert!(offset + type_size <= BLOCK_SZ);
+        self.modified = true;
+        unsafe { &mut *(self.addr_of_offset(offset) as *mut T) }
+    }
+
+    /// Read the value of type `T` at `offset` through `f`.
+    pub fn read<T, V>(&self, offset: usize, f: impl FnOnce(&T) -> V) -> V {
+        f(self.get_ref(offset))
+    }
+
+    /// Modify the value of type `T` at `offset` through `f`.
+    pub fn modify<T, V>(&mut self, offset: usize, f: impl FnOnce(&mut T) -> V) -> V {
+        f(self.get_mut(offset))
+    }
+
+    /// Write back to the backing device if dirty.
+    pub fn sync(&mut self) {
+        if self.modified {
+            self.modified = false;
+            self.block_device.write_block(self.block_id, &self.cache);
+        }
+    }
+}
+
+impl Drop for BlockCache {
+    fn drop(&mut self) {
+        self.sync();
+    }
+}
+
+/// Fixed-capacity block cache: evicts the oldest entry with no outstanding
+/// `Arc` reference when full.
+struct BlockCacheManager {
+    queue: VecDeque<(usize, Arc<Mutex<BlockCache>>)>,
+}
+
+impl BlockCacheManager {
+    fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+        }
+    }
+
+    fn get_block_cache(
+        &mut self,
+        block_id: usize,
+        block_device: Arc<dyn BlockDevice>,
+    ) -> Arc<Mutex<BlockCache>> {
+        if let Some((_, cache)) = self.queue.iter().find(|(id, _)| *id == block_id) {
+            return Arc::clone(cache);
+        }
+        if self.queue.len() == BLOCK_CACHE_SIZE {
+            if let Some(idx) = self
+                .queue
+                .iter()
+                .position(|(_, cache)| Arc::strong_count(cache) == 1)
+            {
+                self.queue.remove(idx);
+            } else {
+                panic!("BlockCacheManager: cache full and every entry is still referenced");
+            }
+        }
+        let block_cache = Arc::new(Mutex::new(BlockCache::new(block_id, Arc::clone(&block_device))));
+        self.queue.push_back((block_id, Arc::clone(&block_cache)));
+        block_cache
+    }
+}
+
+lazy_static! {
+    static ref BLOCK_CACHE_MANAGER: Mutex<BlockCacheManager> = Mutex::new(BlockCacheManager::new());
+}
+
+/// Get (loading if necessary) the cache entry for `block_id`.
+pub fn get_block_cache(block_id: usize, block_device: Arc<dyn BlockDevice>) -> Arc<Mutex<BlockCache>> {
+    BLOCK_CACHE_MANAGER
+        .lock()
+        .get_block_cache(block_id, block_device)
+}
+
+/// Flush every currently cached block back to its device.
+pub fn block_cache_sync_all() {
+    let manager = BLOCK_CACHE_MANAGER.lock();
+    for (_, cache) in manager.queue.iter() {
+        cache.lock().sync();
+    }
+}
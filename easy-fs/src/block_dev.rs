@@ -0,0 +1,14 @@
+//! The interface easy-fs needs from whatever backs its blocks — a RAM disk,
+//! a virtio block device, or (in this tree) the in-memory placeholder in
+//! `os`'s `fs` module.
+
+use core::any::Any;
+
+/// Read/write fixed-size blocks by index; implementations are responsible
+/// for their own internal locking.
+pub trait BlockDevice: Send + Sync + Any {
+    /// Read block `block_id` into `buf` (exactly one block).
+    fn read_block(&self, block_id: usize, buf: &mut [u8]);
+    /// Write `buf` (exactly one block) to block `block_id`.
+    fn write_block(&self, block_id: usize, buf: &[u8]);
+}
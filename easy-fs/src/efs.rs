@@ -0,0 +1,188 @@
+//! Top-level filesystem: the on-disk super block layout, inode/data-bitmap
+//! allocation, and bootstrapping (`create`/`open`) a block device into the
+//! `Inode` tree `vfs.rs` walks.
+
+use super::{block_cache_sync_all, get_block_cache, Bitmap, BlockDevice, DiskInode, DiskInodeType, Inode, BLOCK_SZ};
+use alloc::sync::Arc;
+use spin::Mutex;
+
+/// Stamped into block 0 so `open` can sanity-check the device really holds
+/// an easy-fs image before trusting the rest of its super block.
+const EFS_MAGIC: u32 = 0x3b80_0001;
+const INODE_SIZE: usize = core::mem::size_of::<DiskInode>();
+
+/// On-disk block 0: identifies the image and records where each region
+/// starts, so `open` can rebuild an `EasyFileSystem` without reformatting.
+#[repr(C)]
+struct SuperBlock {
+    magic: u32,
+    total_blocks: u32,
+    inode_bitmap_blocks: u32,
+    inode_area_blocks: u32,
+    data_bitmap_blocks: u32,
+    data_area_blocks: u32,
+}
+
+impl SuperBlock {
+    fn initialize(
+        &mut self,
+        total_blocks: u32,
+        inode_bitmap_blocks: u32,
+        inode_area_blocks: u32,
+        data_bitmap_blocks: u32,
+        data_area_blocks: u32,
+    ) {
+        *self = Self {
+            magic: EFS_MAGIC,
+            total_blocks,
+            inode_bitmap_blocks,
+            inode_area_blocks,
+            data_bitmap_blocks,
+            data_area_blocks,
+        };
+    }
+    fn is_valid(&self) -> bool {
+        self.magic == EFS_MAGIC
+    }
+}
+
+/// The whole filesystem: inode/data bitmaps plus where their corresponding
+/// areas start on disk, shared (behind a `Mutex`) by every `Inode` that
+/// belongs to it.
+pub struct EasyFileSystem {
+    /// Backing block device.
+    pub block_device: Arc<dyn BlockDevice>,
+    /// Free-inode bitmap.
+    pub inode_bitmap: Bitmap,
+    /// Free-data-block bitmap.
+    pub data_bitmap: Bitmap,
+    inode_area_start_block: u32,
+    data_area_start_block: u32,
+}
+
+impl EasyFileSystem {
+    /// Format `block_device` as a fresh `total_blocks`-block filesystem,
+    /// dedicating `inode_bitmap_blocks` blocks to the inode bitmap (and
+    /// however many blocks that implies for the inode table itself), and
+    /// return it wrapping a freshly initialized root directory.
+    pub fn create(
+        block_device: Arc<dyn BlockDevice>,
+        total_blocks: u32,
+        inode_bitmap_blocks: u32,
+    ) -> Arc<Mutex<Self>> {
+        let inode_bitmap = Bitmap::new(1, inode_bitmap_blocks as usize);
+        let inode_num = inode_bitmap.maximum();
+        let inodes_per_block = BLOCK_SZ / INODE_SIZE;
+        let inode_area_blocks = ((inode_num + inodes_per_block - 1) / inodes_per_block) as u32;
+        let inode_total_blocks = inode_bitmap_blocks + inode_area_blocks;
+        let data_total_blocks = total_blocks - 1 - inode_total_blocks;
+        // One data-bitmap block can track `BLOCK_SZ * 8` data blocks, so
+        // reserve one bitmap block per that many data blocks (+1 to round
+        // up for the bitmap blocks' own footprint).
+        let data_bitmap_blocks = (data_total_blocks + 4096) / 4097;
+        let data_area_blocks = data_total_blocks - data_bitmap_blocks;
+        let data_bitmap = Bitmap::new((1 + inode_total_blocks) as usize, data_bitmap_blocks as usize);
+        let mut efs = Self {
+            block_device: Arc::clone(&block_device),
+            inode_bitmap,
+            data_bitmap,
+            inode_area_start_block: 1 + inode_bitmap_blocks,
+            data_area_start_block: 1 + inode_total_blocks + data_bitmap_blocks,
+        };
+        for block_id in 0..total_blocks {
+            get_block_cache(block_id as usize, Arc::clone(&block_device))
+                .lock()
+                .modify(0, |data_block: &mut [u8; BLOCK_SZ]| {
+                    data_block.iter_mut().for_each(|b| *b = 0);
+                });
+        }
+        get_block_cache(0, Arc::clone(&block_device))
+            .lock()
+            .modify(0, |super_block: &mut SuperBlock| {
+                super_block.initialize(
+                    total_blocks,
+                    inode_bitmap_blocks,
+                    inode_area_blocks,
+                    data_bitmap_blocks,
+                    data_area_blocks,
+                );
+            });
+        assert_eq!(efs.alloc_inode(), 0, "root directory must be inode 0");
+        let (root_block_id, root_block_offset) = efs.get_disk_inode_pos(0);
+        get_block_cache(root_block_id as usize, Arc::clone(&block_device))
+            .lock()
+            .modify(root_block_offset, |disk_inode: &mut DiskInode| {
+                disk_inode.initialize(DiskInodeType::Directory);
+            });
+        block_cache_sync_all();
+        Arc::new(Mutex::new(efs))
+    }
+
+    /// Load an already-formatted filesystem off `block_device`.
+    pub fn open(block_device: Arc<dyn BlockDevice>) -> Arc<Mutex<Self>> {
+        let block = get_block_cache(0, Arc::clone(&block_device));
+        let guard = block.lock();
+        let efs = guard.read(0, |super_block: &SuperBlock| {
+            assert!(super_block.is_valid(), "invalid easy-fs super block");
+            let inode_total_blocks = super_block.inode_bitmap_blocks + super_block.inode_area_blocks;
+            Self {
+                block_device: Arc::clone(&block_device),
+                inode_bitmap: Bitmap::new(1, super_block.inode_bitmap_blocks as usize),
+                data_bitmap: Bitmap::new(
+                    (1 + inode_total_blocks) as usize,
+                    super_block.data_bitmap_blocks as usize,
+                ),
+                inode_area_start_block: 1 + super_block.inode_bitmap_blocks,
+                data_area_start_block: 1 + inode_total_blocks + super_block.data_bitmap_blocks,
+            }
+        });
+        Arc::new(Mutex::new(efs))
+    }
+
+    /// The root directory's `Inode` (always inode id 0).
+    pub fn root_inode(efs: &Arc<Mutex<Self>>) -> Inode {
+        let (block_id, block_offset) = efs.lock().get_disk_inode_pos(0);
+        let block_device = Arc::clone(&efs.lock().block_device);
+        Inode::new(block_id, block_offset, Arc::clone(efs), block_device)
+    }
+
+    /// Where inode `inode_id`'s `DiskInode` lives on disk.
+    pub fn get_disk_inode_pos(&self, inode_id: u32) -> (u32, usize) {
+        let inodes_per_block = (BLOCK_SZ / INODE_SIZE) as u32;
+        let block_id = self.inode_area_start_block + inode_id / inodes_per_block;
+        (block_id, (inode_id % inodes_per_block) as usize * INODE_SIZE)
+    }
+
+    /// Reverse of `get_disk_inode_pos`: recover the inode id a `DiskInode`
+    /// at `(block_id, block_offset)` belongs to.
+    pub fn get_inode_id(&self, block_id: usize, block_offset: usize) -> u32 {
+        let inodes_per_block = (BLOCK_SZ / INODE_SIZE) as u32;
+        (block_id as u32 - self.inode_area_start_block) * inodes_per_block
+            + (block_offset / INODE_SIZE) as u32
+    }
+
+    /// Allocate a fresh inode id.
+    pub fn alloc_inode(&mut self) -> u32 {
+        self.inode_bitmap.alloc(&self.block_device).unwrap() as u32
+    }
+
+    /// Allocate a fresh data block, returning its on-disk block id (already
+    /// offset past the data area's start).
+    pub fn alloc_data(&mut self) -> u32 {
+        self.data_bitmap.alloc(&self.block_device).unwrap() as u32 + self.data_area_start_block
+    }
+
+    /// Free a data block previously returned by `alloc_data`, zeroing it so
+    /// stale contents never leak into whatever reuses the id.
+    pub fn dealloc_data(&mut self, block_id: u32) {
+        get_block_cache(block_id as usize, Arc::clone(&self.block_device))
+            .lock()
+            .modify(0, |data_block: &mut [u8; BLOCK_SZ]| {
+                data_block.iter_mut().for_each(|b| *b = 0);
+            });
+        self.data_bitmap.dealloc(
+            &self.block_device,
+            (block_id - self.data_area_start_block) as usize,
+        );
+    }
+}
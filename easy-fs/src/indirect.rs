@@ -0,0 +1,122 @@
+//! Double- and triple-indirect block layout for [`super::DiskInode`].
+//!
+//! The direct/single-indirect scheme caps file size well below what a real
+//! workload needs. This module adds the two extra addressing levels
+//! `DiskInode::get_block_id` walks through once a logical block index runs
+//! past the single-indirect range, mirroring `IndirectBlock`/
+//! `DoubleIndirectBlock`/`TripleIndirectBlock` in the referenced
+//! filesystem.
+
+use super::{get_block_cache, BlockDevice, BLOCK_SZ};
+use alloc::sync::Arc;
+
+/// Block ids fit in a `u32`, so one block holds this many of them.
+pub const INDIRECT_ENTRIES: usize = BLOCK_SZ / core::mem::size_of::<u32>();
+
+/// One level of indirection: `INDIRECT_ENTRIES` block ids, each pointing
+/// directly at a data block.
+#[repr(C)]
+pub struct IndirectBlock {
+    /// Data block ids this block indexes.
+    pub entries: [u32; INDIRECT_ENTRIES],
+}
+
+impl IndirectBlock {
+    /// An all-zero (unallocated) indirect block.
+    pub fn empty() -> Self {
+        Self {
+            entries: [0; INDIRECT_ENTRIES],
+        }
+    }
+}
+
+/// Two levels of indirection: each entry points at an [`IndirectBlock`],
+/// covering `INDIRECT_ENTRIES^2` data blocks.
+#[repr(C)]
+pub struct DoubleIndirectBlock {
+    /// Block ids of the [`IndirectBlock`]s this block indexes.
+    pub entries: [u32; INDIRECT_ENTRIES],
+}
+
+impl DoubleIndirectBlock {
+    /// An all-zero (unallocated) double-indirect block.
+    pub fn empty() -> Self {
+        Self {
+            entries: [0; INDIRECT_ENTRIES],
+        }
+    }
+}
+
+/// Three levels of indirection: each entry points at a
+/// [`DoubleIndirectBlock`], covering `INDIRECT_ENTRIES^3` data blocks.
+#[repr(C)]
+pub struct TripleIndirectBlock {
+    /// Block ids of the [`DoubleIndirectBlock`]s this block indexes.
+    pub entries: [u32; INDIRECT_ENTRIES],
+}
+
+impl TripleIndirectBlock {
+    /// An all-zero (unallocated) triple-indirect block.
+    pub fn empty() -> Self {
+        Self {
+            entries: [0; INDIRECT_ENTRIES],
+        }
+    }
+}
+
+/// Resolve logical block index `inner_id` to a physical block id by walking
+/// the appropriate number of indirection levels, allocating nothing (a
+/// `0` entry means "not yet allocated" and is the caller's problem during
+/// `increase_size`).
+///
+/// `direct_bound`/`indirect1_bound`/`indirect2_bound` are the exclusive
+/// upper logical-block bounds of the direct, single-indirect and
+/// double-indirect ranges respectively; anything at or past
+/// `indirect2_bound` falls into the triple-indirect range.
+pub fn resolve_block_id(
+    inner_id: usize,
+    direct: &[u32],
+    direct_bound: usize,
+    indirect1: u32,
+    indirect1_bound: usize,
+    indirect2: u32,
+    indirect2_bound: usize,
+    indirect3: u32,
+    block_device: &Arc<dyn BlockDevice>,
+) -> u32 {
+    if inner_id < direct_bound {
+        return direct[inner_id];
+    }
+    if inner_id < indirect1_bound {
+        let idx = inner_id - direct_bound;
+        return get_block_cache(indirect1 as usize, Arc::clone(block_device))
+            .lock()
+            .read(0, |blk: &IndirectBlock| blk.entries[idx]);
+    }
+    if inner_id < indirect2_bound {
+        let idx = inner_id - indirect1_bound;
+        let (l1, l2) = (idx / INDIRECT_ENTRIES, idx % INDIRECT_ENTRIES);
+        let level1 = get_block_cache(indirect2 as usize, Arc::clone(block_device))
+            .lock()
+            .read(0, |blk: &DoubleIndirectBlock| blk.entries[l1]);
+        return get_block_cache(level1 as usize, Arc::clone(block_device))
+            .lock()
+            .read(0, |blk: &IndirectBlock| blk.entries[l2]);
+    }
+    // Triple-indirect range.
+    let idx = inner_id - indirect2_bound;
+    let (l1, rest) = (
+        idx / (INDIRECT_ENTRIES * INDIRECT_ENTRIES),
+        idx % (INDIRECT_ENTRIES * INDIRECT_ENTRIES),
+    );
+    let (l2, l3) = (rest / INDIRECT_ENTRIES, rest % INDIRECT_ENTRIES);
+    let level1 = get_block_cache(indirect3 as usize, Arc::clone(block_device))
+        .lock()
+        .read(0, |blk: &TripleIndirectBlock| blk.entries[l1]);
+    let level2 = get_block_cache(level1 as usize, Arc::clone(block_device))
+        .lock()
+        .read(0, |blk: &DoubleIndirectBlock| blk.entries[l2]);
+    get_block_cache(level2 as usize, Arc::clone(block_device))
+        .lock()
+        .read(0, |blk: &IndirectBlock| blk.entries[l3])
+}
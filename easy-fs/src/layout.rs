@@ -0,0 +1,493 @@
+//! On-disk inode and directory-entry layout: [`DiskInode`] (metadata plus
+//! the direct and single-/double-/triple-indirect block pointers locating a
+//! file's data) and
+//! [`DirEntry`] (the fixed-size `name -> inode id` records a directory's
+//! data blocks hold).
+
+use super::indirect::{resolve_block_id, INDIRECT_ENTRIES};
+use super::vfs::{S_IFDIR, S_IFREG};
+use super::{get_block_cache, BlockDevice, BLOCK_SZ};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// `mode` bit for a symlink inode (`S_IFLNK`), not otherwise exposed since
+/// nothing outside this module inspects a symlink's mode bits.
+const S_IFLNK: u16 = 0o120000;
+
+/// How many data-block ids a `DiskInode` stores inline before falling back
+/// to the single-indirect block.
+const INODE_DIRECT_COUNT: usize = 28;
+/// How many block ids fit in one indirect block.
+const INODE_INDIRECT1_COUNT: usize = BLOCK_SZ / 4;
+/// Exclusive upper bound of the logical block range covered by `direct`.
+const DIRECT_BOUND: usize = INODE_DIRECT_COUNT;
+/// Exclusive upper bound of the logical block range covered by `direct` plus
+/// `indirect1`.
+const INDIRECT1_BOUND: usize = DIRECT_BOUND + INODE_INDIRECT1_COUNT;
+/// How many data blocks `indirect2` covers: one [`DoubleIndirectBlock`]
+/// fanning out to `INDIRECT_ENTRIES` [`IndirectBlock`]s.
+const INODE_INDIRECT2_COUNT: usize = INDIRECT_ENTRIES * INDIRECT_ENTRIES;
+/// Exclusive upper bound of the logical block range covered by `direct`,
+/// `indirect1` and `indirect2`.
+const INDIRECT2_BOUND: usize = INDIRECT1_BOUND + INODE_INDIRECT2_COUNT;
+
+/// One indirect block's worth of block ids, as stored on disk.
+type IndirectBlockData = [u32; BLOCK_SZ / 4];
+
+/// Which kind of file an inode describes.
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum DiskInodeType {
+    /// A regular file.
+    File,
+    /// A directory: its data blocks are packed with `DirEntry`s.
+    Directory,
+    /// A symbolic link: its data blocks hold the literal target path.
+    SymLink,
+}
+
+/// On-disk inode: metadata (size, owner, permissions, link count) plus the
+/// direct and single-/double-/triple-indirect block pointers needed to
+/// locate a file's data.
+#[repr(C)]
+pub struct DiskInode {
+    /// File size in bytes.
+    pub size: u32,
+    /// Direct data-block pointers, `0` meaning unallocated.
+    pub direct: [u32; INODE_DIRECT_COUNT],
+    /// Single-indirect block pointer, `0` meaning unallocated.
+    pub indirect1: u32,
+    /// Double-indirect block pointer, `0` meaning unallocated.
+    pub indirect2: u32,
+    /// Triple-indirect block pointer, `0` meaning unallocated.
+    pub indirect3: u32,
+    type_: DiskInodeType,
+    uid: u32,
+    gid: u32,
+    mode: u16,
+    link_count: u32,
+}
+
+impl DiskInode {
+    /// Reset this (freshly allocated) inode to an empty file/directory/
+    /// symlink of the given type, owned by nobody with no links yet.
+    pub fn initialize(&mut self, type_: DiskInodeType) {
+        self.size = 0;
+        self.direct = [0; INODE_DIRECT_COUNT];
+        self.indirect1 = 0;
+        self.indirect2 = 0;
+        self.indirect3 = 0;
+        self.type_ = type_;
+        self.uid = 0;
+        self.gid = 0;
+        self.mode = match type_ {
+            DiskInodeType::Directory => S_IFDIR,
+            DiskInodeType::File => S_IFREG,
+            DiskInodeType::SymLink => S_IFLNK,
+        };
+        self.link_count = 0;
+    }
+
+    /// Whether this inode is a directory.
+    pub fn is_dir(&self) -> bool {
+        self.type_ == DiskInodeType::Directory
+    }
+    /// Whether this inode is a symlink.
+    pub fn is_symlink(&self) -> bool {
+        self.type_ == DiskInodeType::SymLink
+    }
+
+    fn data_blocks(size: u32) -> u32 {
+        ((size as usize + BLOCK_SZ - 1) / BLOCK_SZ) as u32
+    }
+
+    /// How many blocks (data plus any index blocks) a file of `size` bytes
+    /// occupies.
+    pub fn total_blocks(size: u32) -> u32 {
+        let data_blocks = Self::data_blocks(size) as usize;
+        let mut total = data_blocks;
+        if data_blocks > DIRECT_BOUND {
+            total += 1; // the indirect1 block itself
+        }
+        if data_blocks > INDIRECT1_BOUND {
+            let indirect2_data = data_blocks - INDIRECT1_BOUND;
+            total += 1; // the indirect2 (top-level) block itself
+            // one IndirectBlock per INDIRECT_ENTRIES data blocks fanned out to
+            total += (indirect2_data + INDIRECT_ENTRIES - 1) / INDIRECT_ENTRIES;
+        }
+        if data_blocks > INDIRECT2_BOUND {
+            let indirect3_data = data_blocks - INDIRECT2_BOUND;
+            total += 1; // the indirect3 (top-level) block itself
+            // one DoubleIndirectBlock per INDIRECT_ENTRIES^2 data blocks
+            total += (indirect3_data + INODE_INDIRECT2_COUNT - 1) / INODE_INDIRECT2_COUNT;
+            // one IndirectBlock per INDIRECT_ENTRIES data blocks, across every
+            // double-indirect block engaged so far
+            total += (indirect3_data + INDIRECT_ENTRIES - 1) / INDIRECT_ENTRIES;
+        }
+        total as u32
+    }
+
+    /// How many additional blocks must be allocated to grow this inode to
+    /// `new_size` bytes.
+    pub fn blocks_num_needed(&self, new_size: u32) -> u32 {
+        assert!(new_size >= self.size);
+        Self::total_blocks(new_size) - Self::total_blocks(self.size)
+    }
+
+    /// Resolve logical block index `inner_id` to a physical block id,
+    /// walking as many levels of indirection as `inner_id` needs (see
+    /// [`super::indirect::resolve_block_id`]).
+    pub fn get_block_id(&self, inner_id: usize, block_device: &Arc<dyn BlockDevice>) -> u32 {
+        resolve_block_id(
+            inner_id,
+            &self.direct,
+            DIRECT_BOUND,
+            self.indirect1,
+            INDIRECT1_BOUND,
+            self.indirect2,
+            INDIRECT2_BOUND,
+            self.indirect3,
+            block_device,
+        )
+    }
+
+    /// Fill logical positions `[start, end)` inside the index block
+    /// `block_id`, which is `depth` indirection levels above the data
+    /// blocks (`depth == 1` for an [`super::indirect::IndirectBlock`],
+    /// `2` for a [`super::indirect::DoubleIndirectBlock`], `3` for a
+    /// [`super::indirect::TripleIndirectBlock`] — all three share this same
+    /// `[u32; INDIRECT_ENTRIES]` on-disk shape). Consumes one id from
+    /// `new_blocks` for every position at `depth == 1`, and one id per
+    /// not-yet-visited child index block at deeper levels, recursing into
+    /// each child as its range is reached.
+    fn fill_indirect(
+        block_id: u32,
+        depth: u32,
+        start: usize,
+        end: usize,
+        new_blocks: &mut impl Iterator<Item = u32>,
+        block_device: &Arc<dyn BlockDevice>,
+    ) {
+        let child_span = INDIRECT_ENTRIES.pow(depth - 1);
+        get_block_cache(block_id as usize, Arc::clone(block_device))
+            .lock()
+            .modify(0, |entries: &mut IndirectBlockData| {
+                let mut pos = start;
+                while pos < end {
+                    let slot = pos / child_span;
+                    let slot_start = slot * child_span;
+                    let slot_end = (slot_start + child_span).min(end);
+                    if depth == 1 {
+                        entries[slot] = new_blocks.next().unwrap();
+                    } else {
+                        if pos == slot_start {
+                            entries[slot] = new_blocks.next().unwrap();
+                        }
+                        Self::fill_indirect(
+                            entries[slot],
+                            depth - 1,
+                            pos - slot_start,
+                            slot_end - slot_start,
+                            new_blocks,
+                            block_device,
+                        );
+                    }
+                    pos = slot_end;
+                }
+            });
+    }
+
+    /// Grow this inode to `new_size` bytes, filling in the freshly
+    /// allocated block ids from `new_blocks` (as allocated by the caller via
+    /// `EasyFileSystem::alloc_data`, in order).
+    pub fn increase_size(
+        &mut self,
+        new_size: u32,
+        new_blocks: Vec<u32>,
+        block_device: &Arc<dyn BlockDevice>,
+    ) {
+        let mut current_blocks = Self::data_blocks(self.size) as usize;
+        self.size = new_size;
+        let total_blocks = Self::data_blocks(self.size) as usize;
+        let mut new_blocks = new_blocks.into_iter();
+
+        while current_blocks < total_blocks.min(DIRECT_BOUND) {
+            self.direct[current_blocks] = new_blocks.next().unwrap();
+            current_blocks += 1;
+        }
+        if total_blocks <= DIRECT_BOUND {
+            return;
+        }
+
+        if current_blocks == DIRECT_BOUND {
+            self.indirect1 = new_blocks.next().unwrap();
+        }
+        let indirect1_end = total_blocks.min(INDIRECT1_BOUND) - DIRECT_BOUND;
+        Self::fill_indirect(
+            self.indirect1,
+            1,
+            current_blocks.saturating_sub(DIRECT_BOUND).min(indirect1_end),
+            indirect1_end,
+            &mut new_blocks,
+            block_device,
+        );
+        current_blocks = current_blocks.max(DIRECT_BOUND + indirect1_end);
+        if total_blocks <= INDIRECT1_BOUND {
+            return;
+        }
+
+        if current_blocks == INDIRECT1_BOUND {
+            self.indirect2 = new_blocks.next().unwrap();
+        }
+        let indirect2_end = total_blocks.min(INDIRECT2_BOUND) - INDIRECT1_BOUND;
+        Self::fill_indirect(
+            self.indirect2,
+            2,
+            current_blocks.saturating_sub(INDIRECT1_BOUND).min(indirect2_end),
+            indirect2_end,
+            &mut new_blocks,
+            block_device,
+        );
+        current_blocks = current_blocks.max(INDIRECT1_BOUND + indirect2_end);
+        if total_blocks <= INDIRECT2_BOUND {
+            return;
+        }
+
+        if current_blocks == INDIRECT2_BOUND {
+            self.indirect3 = new_blocks.next().unwrap();
+        }
+        let indirect3_end = total_blocks - INDIRECT2_BOUND;
+        Self::fill_indirect(
+            self.indirect3,
+            3,
+            current_blocks.saturating_sub(INDIRECT2_BOUND).min(indirect3_end),
+            indirect3_end,
+            &mut new_blocks,
+            block_device,
+        );
+    }
+
+    /// Collect every block id an index block at `depth` indirection levels
+    /// holds across its first `count` logical positions into `freed`,
+    /// including every child index block visited along the way (the
+    /// reverse of [`Self::fill_indirect`]).
+    fn collect_indirect(
+        block_id: u32,
+        depth: u32,
+        count: usize,
+        freed: &mut Vec<u32>,
+        block_device: &Arc<dyn BlockDevice>,
+    ) {
+        let child_span = INDIRECT_ENTRIES.pow(depth - 1);
+        let children = (count + child_span - 1) / child_span;
+        get_block_cache(block_id as usize, Arc::clone(block_device))
+            .lock()
+            .read(0, |entries: &IndirectBlockData| {
+                for (slot, entry) in entries.iter().take(children).enumerate() {
+                    if depth == 1 {
+                        freed.push(*entry);
+                    } else {
+                        freed.push(*entry);
+                        let slot_start = slot * child_span;
+                        Self::collect_indirect(
+                            *entry,
+                            depth - 1,
+                            count.saturating_sub(slot_start).min(child_span),
+                            freed,
+                            block_device,
+                        );
+                    }
+                }
+            });
+    }
+
+    /// Shrink this inode to size 0, returning every data block id it held
+    /// (including every now-freed index block, at every indirection level,
+    /// itself) so the caller can hand them back to
+    /// `EasyFileSystem::dealloc_data`.
+    pub fn clear_size(&mut self, block_device: &Arc<dyn BlockDevice>) -> Vec<u32> {
+        let mut freed = Vec::new();
+        let data_blocks = Self::data_blocks(self.size) as usize;
+        self.size = 0;
+        let mut current_blocks = 0usize;
+        while current_blocks < data_blocks.min(DIRECT_BOUND) {
+            freed.push(self.direct[current_blocks]);
+            self.direct[current_blocks] = 0;
+            current_blocks += 1;
+        }
+        if data_blocks <= DIRECT_BOUND {
+            return freed;
+        }
+
+        freed.push(self.indirect1);
+        let indirect1_count = data_blocks.min(INDIRECT1_BOUND) - DIRECT_BOUND;
+        Self::collect_indirect(self.indirect1, 1, indirect1_count, &mut freed, block_device);
+        self.indirect1 = 0;
+        if data_blocks <= INDIRECT1_BOUND {
+            return freed;
+        }
+
+        freed.push(self.indirect2);
+        let indirect2_count = data_blocks.min(INDIRECT2_BOUND) - INDIRECT1_BOUND;
+        Self::collect_indirect(self.indirect2, 2, indirect2_count, &mut freed, block_device);
+        self.indirect2 = 0;
+        if data_blocks <= INDIRECT2_BOUND {
+            return freed;
+        }
+
+        freed.push(self.indirect3);
+        let indirect3_count = data_blocks - INDIRECT2_BOUND;
+        Self::collect_indirect(self.indirect3, 3, indirect3_count, &mut freed, block_device);
+        self.indirect3 = 0;
+        freed
+    }
+
+    /// Read up to `buf.len()` bytes starting at `offset`, clamped to the
+    /// inode's size; returns the number of bytes actually read.
+    pub fn read_at(&self, offset: usize, buf: &mut [u8], block_device: &Arc<dyn BlockDevice>) -> usize {
+        let end = (offset + buf.len()).min(self.size as usize);
+        if offset >= end {
+            return 0;
+        }
+        let mut start = offset;
+        let mut start_block = start / BLOCK_SZ;
+        let mut read_size = 0usize;
+        loop {
+            let end_current_block = ((start / BLOCK_SZ + 1) * BLOCK_SZ).min(end);
+            let block_read_size = end_current_block - start;
+            let dst = &mut buf[read_size..read_size + block_read_size];
+            get_block_cache(
+                self.get_block_id(start_block, block_device) as usize,
+                Arc::clone(block_device),
+            )
+            .lock()
+            .read(0, |data_block: &[u8; BLOCK_SZ]| {
+                dst.copy_from_slice(&data_block[start % BLOCK_SZ..start % BLOCK_SZ + block_read_size]);
+            });
+            read_size += block_read_size;
+            if end_current_block == end {
+                break;
+            }
+            start_block += 1;
+            start = end_current_block;
+        }
+        read_size
+    }
+
+    /// Write `buf` starting at `offset`, clamped to the inode's *current*
+    /// size (callers grow the inode via `increase_size` first); returns the
+    /// number of bytes actually written.
+    pub fn write_at(&mut self, offset: usize, buf: &[u8], block_device: &Arc<dyn BlockDevice>) -> usize {
+        let end = (offset + buf.len()).min(self.size as usize);
+        assert!(offset <= end);
+        let mut start = offset;
+        let mut start_block = start / BLOCK_SZ;
+        let mut write_size = 0usize;
+        loop {
+            let end_current_block = ((start / BLOCK_SZ + 1) * BLOCK_SZ).min(end);
+            let block_write_size = end_current_block - start;
+            let src = &buf[write_size..write_size + block_write_size];
+            get_block_cache(
+                self.get_block_id(start_block, block_device) as usize,
+                Arc::clone(block_device),
+            )
+            .lock()
+            .modify(0, |data_block: &mut [u8; BLOCK_SZ]| {
+                data_block[start % BLOCK_SZ..start % BLOCK_SZ + block_write_size].copy_from_slice(src);
+            });
+            write_size += block_write_size;
+            if end_current_block == end {
+                break;
+            }
+            start_block += 1;
+            start = end_current_block;
+        }
+        write_size
+    }
+
+    /// Bump the hard-link count, returning the new value.
+    pub fn hard_link_add(&mut self) -> u32 {
+        self.link_count += 1;
+        self.link_count
+    }
+    /// Drop the hard-link count, returning the new value.
+    pub fn hard_link_del(&mut self) -> u32 {
+        self.link_count = self.link_count.saturating_sub(1);
+        self.link_count
+    }
+    /// Current hard-link count.
+    pub fn get_hard_link_num(&self) -> u32 {
+        self.link_count
+    }
+    /// Owning user id.
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+    /// Owning group id.
+    pub fn gid(&self) -> u32 {
+        self.gid
+    }
+    /// `rwx`-plus-type mode bits.
+    pub fn mode(&self) -> u16 {
+        self.mode
+    }
+    /// Set the owning uid/gid.
+    pub fn set_owner(&mut self, uid: u32, gid: u32) {
+        self.uid = uid;
+        self.gid = gid;
+    }
+    /// Set the permission bits, leaving the type bits untouched.
+    pub fn set_mode(&mut self, mode: u16) {
+        let type_bits = self.mode & !0o7777;
+        self.mode = type_bits | (mode & 0o7777);
+    }
+}
+
+/// Maximum length of one path component stored in a `DirEntry`.
+const NAME_LENGTH_LIMIT: usize = 27;
+/// On-disk size of one `DirEntry`.
+pub const DIRENT_SZ: usize = 32;
+
+/// One `name -> inode id` record in a directory's data blocks.
+#[repr(C)]
+pub struct DirEntry {
+    name: [u8; NAME_LENGTH_LIMIT + 1],
+    inode_number: u32,
+}
+
+impl DirEntry {
+    /// An empty (all-zero) entry, used as a scratch buffer and to mark a
+    /// deleted slot.
+    pub fn empty() -> Self {
+        Self {
+            name: [0u8; NAME_LENGTH_LIMIT + 1],
+            inode_number: 0,
+        }
+    }
+    /// An entry mapping `name` to `inode_number`.
+    pub fn new(name: &str, inode_number: u32) -> Self {
+        let mut bytes = [0u8; NAME_LENGTH_LIMIT + 1];
+        bytes[..name.len()].copy_from_slice(name.as_bytes());
+        Self {
+            name: bytes,
+            inode_number,
+        }
+    }
+    /// View this entry as its raw on-disk bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self as *const _ as *const u8, DIRENT_SZ) }
+    }
+    /// Mutably view this entry as its raw on-disk bytes (for `read_at` to
+    /// fill in place).
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self as *mut _ as *mut u8, DIRENT_SZ) }
+    }
+    /// The entry's name, up to the first NUL byte.
+    pub fn name(&self) -> &str {
+        let len = self.name.iter().position(|&b| b == 0).unwrap_or(self.name.len());
+        core::str::from_utf8(&self.name[..len]).unwrap()
+    }
+    /// The inode id this entry points at.
+    pub fn inode_id(&self) -> u32 {
+        self.inode_number
+    }
+}
@@ -0,0 +1,33 @@
+//! easy-fs: a small from-scratch filesystem. This crate owns the on-disk
+//! layout (`layout`), the block cache every read/write goes through
+//! (`block_cache`), the `BlockDevice` trait it's backed by (`block_dev`),
+//! the free-space bitmaps (`bitmap`), the top-level `EasyFileSystem`
+//! bootstrap (`efs`), double-/triple-indirect addressing for large files
+//! (`indirect`), statfs-style capacity accounting (`statfs`), and the
+//! `Inode` tree syscalls walk (`vfs`).
+#![no_std]
+
+extern crate alloc;
+
+mod bitmap;
+mod block_cache;
+mod block_dev;
+mod efs;
+mod indirect;
+mod layout;
+mod statfs;
+mod vfs;
+
+pub use bitmap::Bitmap;
+pub use block_cache::{block_cache_sync_all, get_block_cache, BlockCache, BLOCK_SZ};
+pub use block_dev::BlockDevice;
+pub use efs::EasyFileSystem;
+pub use indirect::{
+    resolve_block_id, DoubleIndirectBlock, IndirectBlock, TripleIndirectBlock, INDIRECT_ENTRIES,
+};
+pub use layout::{DirEntry, DiskInode, DiskInodeType, DIRENT_SZ};
+pub use statfs::Statfs;
+pub use vfs::{
+    check_access, split_path, Inode, ACCESS_EXEC, ACCESS_READ, ACCESS_WRITE, ELOOP, ENOTEMPTY,
+    MAX_FOLLOW_SYMLINK_TIMES, S_IFDIR, S_IFREG,
+};
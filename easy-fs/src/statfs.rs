@@ -0,0 +1,34 @@
+//! Live capacity/free-space accounting, surfaced to userspace through
+//! `sys_statfs`.
+
+use super::EasyFileSystem;
+
+/// Mirrors the `ReplyStatfs` reply used by the referenced FUSE filesystem:
+/// total and free counts for both data blocks and inodes.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Statfs {
+    /// Number of data-block ids the data bitmap can track (its bit
+    /// capacity, which can exceed the blocks actually in the data area).
+    pub blocks_total: u32,
+    /// Data-bitmap bits not currently set, counted against that same
+    /// capacity.
+    pub blocks_free: u32,
+    /// Total number of inodes the filesystem has room for.
+    pub inodes_total: u32,
+    /// Inodes not currently allocated to any file/directory.
+    pub inodes_free: u32,
+}
+
+impl EasyFileSystem {
+    /// Count unset bits in the inode/data bitmaps against their total sizes
+    /// to report live capacity and free-space figures.
+    pub fn statfs(&self) -> Statfs {
+        Statfs {
+            blocks_total: self.data_bitmap.total_bits() as u32,
+            blocks_free: self.data_bitmap.count_free(&self.block_device) as u32,
+            inodes_total: self.inode_bitmap.total_bits() as u32,
+            inodes_free: self.inode_bitmap.count_free(&self.block_device) as u32,
+        }
+    }
+}
@@ -1,11 +1,24 @@
 use super::{
     block_cache_sync_all, get_block_cache, BlockDevice, DirEntry, DiskInode, DiskInodeType,
-    EasyFileSystem, DIRENT_SZ,
+    EasyFileSystem, Statfs, DIRENT_SZ,
 };
 use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 use spin::{Mutex, MutexGuard};
+
+/// Maximum number of symlink hops `find_following_symlinks` will chase
+/// before giving up and reporting [`ELOOP`], same bound used by the
+/// referenced VFS to turn a cyclic link into an error instead of a hang.
+pub const MAX_FOLLOW_SYMLINK_TIMES: usize = 40;
+/// Too many levels of symbolic links (mirrors the POSIX `ELOOP` errno),
+/// returned by path resolution when [`MAX_FOLLOW_SYMLINK_TIMES`] is
+/// exceeded.
+pub const ELOOP: isize = -40;
+/// Directory not empty (mirrors the POSIX `ENOTEMPTY` errno), returned by
+/// `rmdir` when the target still has entries besides `.`/`..`.
+pub const ENOTEMPTY: isize = -39;
+
 /// Virtual filesystem layer over easy-fs
 pub struct Inode {
     block_id: usize,
@@ -75,7 +88,8 @@ impl Inode {
     pub fn find_inode_id_by_pos(&self) -> u32 {
         self.fs.lock().get_inode_id(self.block_id, self.block_offset)
     }
-    /// Find inode under current inode by name
+    /// Find inode under current inode by name, without following a symlink
+    /// if `name` happens to resolve to one (`O_NOFOLLOW`-style lookup).
     pub fn find(&self, name: &str) -> Option<Arc<Inode>> {
         let fs = self.fs.lock();
         self.read_disk_inode(|disk_inode| {
@@ -90,6 +104,72 @@ impl Inode {
             })
         })
     }
+    /// Find inode under current inode by name, transparently following
+    /// symlinks (bounded by [`MAX_FOLLOW_SYMLINK_TIMES`]). Returns
+    /// `Err(ELOOP)` if resolution doesn't terminate, which catches cyclic
+    /// links instead of looping forever.
+    pub fn find_following_symlinks(&self, name: &str) -> Result<Option<Arc<Inode>>, isize> {
+        let mut current_name = alloc::string::String::from(name);
+        for _ in 0..MAX_FOLLOW_SYMLINK_TIMES {
+            let Some(inode) = self.find(current_name.as_str()) else {
+                return Ok(None);
+            };
+            if !inode.is_symlink() {
+                return Ok(Some(inode));
+            }
+            current_name = inode.read_link();
+        }
+        Err(ELOOP)
+    }
+    /// Create a symlink named `linkpath` whose contents are the literal
+    /// `target` path string (not resolved at creation time).
+    pub fn symlink(&self, linkpath: &str, target: &str) -> Option<Arc<Inode>> {
+        let mut fs = self.fs.lock();
+        let exists = self.read_disk_inode(|root_inode| {
+            assert!(root_inode.is_dir());
+            self.find_inode_id(linkpath, root_inode)
+        });
+        if exists.is_some() {
+            return None;
+        }
+        let new_inode_id = fs.alloc_inode();
+        let (new_inode_block_id, new_inode_block_offset) = fs.get_disk_inode_pos(new_inode_id);
+        get_block_cache(new_inode_block_id as usize, Arc::clone(&self.block_device))
+            .lock()
+            .modify(new_inode_block_offset, |new_inode: &mut DiskInode| {
+                new_inode.initialize(DiskInodeType::SymLink);
+            });
+        let inode = Arc::new(Self::new(
+            new_inode_block_id as usize,
+            new_inode_block_offset,
+            self.fs.clone(),
+            self.block_device.clone(),
+        ));
+        inode.modify_disk_inode(|link_inode| {
+            self.increase_size(target.len() as u32, link_inode, &mut fs);
+            link_inode.write_at(0, target.as_bytes(), &self.block_device);
+        });
+        self.modify_disk_inode(|root_inode| {
+            let file_count = (root_inode.size as usize) / DIRENT_SZ;
+            let new_size = (file_count + 1) * DIRENT_SZ;
+            self.increase_size(new_size as u32, root_inode, &mut fs);
+            let dirent = DirEntry::new(linkpath, new_inode_id);
+            root_inode.write_at(file_count * DIRENT_SZ, dirent.as_bytes(), &self.block_device);
+        });
+        block_cache_sync_all();
+        Some(inode)
+    }
+    /// Whether this inode is a symlink.
+    pub fn is_symlink(&self) -> bool {
+        self.read_disk_inode(DiskInode::is_symlink)
+    }
+    /// Read the target path stored in a symlink's data blocks.
+    pub fn read_link(&self) -> alloc::string::String {
+        let size = self.read_disk_inode(|d| d.size as usize);
+        let mut buf = alloc::vec![0u8; size];
+        self.read_at(0, &mut buf);
+        alloc::string::String::from_utf8_lossy(&buf).into_owned()
+    }
     /// Increase the size of a disk inode
     fn increase_size(
         &self,
@@ -107,8 +187,9 @@ impl Inode {
         }
         disk_inode.increase_size(new_size, v, &self.block_device);
     }
-    /// Create inode under current inode by name
-    pub fn create(&self, name: &str) -> Option<Arc<Inode>> {
+    /// Create inode under current inode by name, owned by `(uid, gid)` with
+    /// the given permission bits (the `S_IFREG` type bit is set for you).
+    pub fn create(&self, name: &str, uid: u32, gid: u32, mode: u16) -> Option<Arc<Inode>> {
         let mut fs = self.fs.lock();
         let op = |root_inode: &DiskInode| {
             // assert it is a directory
@@ -128,6 +209,8 @@ impl Inode {
             .lock()
             .modify(new_inode_block_offset, |new_inode: &mut DiskInode| {
                 new_inode.initialize(DiskInodeType::File);
+                new_inode.set_owner(uid, gid);
+                new_inode.set_mode(S_IFREG | (mode & 0o777));
             });
         self.modify_disk_inode(|root_inode| {
             // append file in the dirent
@@ -270,6 +353,10 @@ impl Inode {
         let mut fs = self.fs.lock();
         self.modify_disk_inode(|disk_inode| {
             let size = disk_inode.size;
+            // `clear_size` also frees the double-/triple-indirect index
+            // blocks now that `DiskInode` supports them, so
+            // `total_blocks(size)` must (and does) count that index-block
+            // overhead for this assertion to still hold.
             let data_blocks_dealloc = disk_inode.clear_size(&self.block_device);
             assert!(data_blocks_dealloc.len() == DiskInode::total_blocks(size) as usize);
             for data_block in data_blocks_dealloc.into_iter() {
@@ -278,6 +365,10 @@ impl Inode {
         });
         block_cache_sync_all();
     }
+    /// Current file size in bytes.
+    pub fn size(&self) -> u64 {
+        self.read_disk_inode(|d| d.size as u64)
+    }
     /// Get inode type
     pub fn is_dir(&self) -> bool {
         self.read_disk_inode(|d| d.is_dir())
@@ -290,4 +381,204 @@ impl Inode {
     pub fn get_hard_link_num(&self) -> u32 {
         self.read_disk_inode(DiskInode::get_hard_link_num)
     }
+    /// Owning user id
+    pub fn uid(&self) -> u32 {
+        self.read_disk_inode(DiskInode::uid)
+    }
+    /// Owning group id
+    pub fn gid(&self) -> u32 {
+        self.read_disk_inode(DiskInode::gid)
+    }
+    /// `rwx`-plus-type mode bits (`S_IFDIR`/`S_IFREG` and the owner/group/
+    /// other permission triples)
+    pub fn mode(&self) -> u16 {
+        self.read_disk_inode(DiskInode::mode)
+    }
+    /// Set the owning uid/gid, used right after `create`/`create_link`
+    /// initialize a fresh inode for its creating process.
+    pub fn set_owner(&self, uid: u32, gid: u32) {
+        self.modify_disk_inode(|disk_inode| disk_inode.set_owner(uid, gid));
+    }
+    /// Set the permission bits, leaving the type bits untouched.
+    pub fn set_mode(&self, mode: u16) {
+        self.modify_disk_inode(|disk_inode| disk_inode.set_mode(mode));
+    }
+    /// Resolve a (possibly multi-component, possibly absolute) path against
+    /// this inode, following symlinks per-component. `/a/b/c` resolves `a`
+    /// under `self`, `b` under `a`, and `c` under `b`. An empty path
+    /// resolves to `self`.
+    pub fn resolve_path(self: &Arc<Self>, path: &str) -> Result<Option<Arc<Inode>>, isize> {
+        let mut current = self.clone();
+        for component in split_path(path) {
+            match current.find_following_symlinks(component)? {
+                Some(next) => current = next,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(current))
+    }
+    /// Create a child directory named `name`, with `.` and `..` dirents
+    /// already populated and this inode's hard-link count bumped for the
+    /// child's `..` reference.
+    pub fn mkdir(&self, name: &str, uid: u32, gid: u32, mode: u16) -> Option<Arc<Inode>> {
+        let mut fs = self.fs.lock();
+        let exists = self.read_disk_inode(|root_inode| {
+            assert!(root_inode.is_dir());
+            self.find_inode_id(name, root_inode)
+        });
+        if exists.is_some() {
+            return None;
+        }
+        let new_inode_id = fs.alloc_inode();
+        let (new_inode_block_id, new_inode_block_offset) = fs.get_disk_inode_pos(new_inode_id);
+        get_block_cache(new_inode_block_id as usize, Arc::clone(&self.block_device))
+            .lock()
+            .modify(new_inode_block_offset, |new_inode: &mut DiskInode| {
+                new_inode.initialize(DiskInodeType::Directory);
+                new_inode.set_owner(uid, gid);
+                new_inode.set_mode(S_IFDIR | (mode & 0o777));
+            });
+        let child = Arc::new(Self::new(
+            new_inode_block_id as usize,
+            new_inode_block_offset,
+            self.fs.clone(),
+            self.block_device.clone(),
+        ));
+        // write "." and ".." dirents into the new directory
+        child.modify_disk_inode(|child_inode| {
+            self.increase_size(2 * DIRENT_SZ as u32, child_inode, &mut fs);
+            let self_id = fs.get_inode_id(self.block_id, self.block_offset);
+            child_inode.write_at(0, DirEntry::new(".", new_inode_id).as_bytes(), &self.block_device);
+            child_inode.write_at(
+                DIRENT_SZ,
+                DirEntry::new("..", self_id).as_bytes(),
+                &self.block_device,
+            );
+        });
+        // link the new directory into this one, and account for ".."
+        self.modify_disk_inode(|root_inode| {
+            let file_count = (root_inode.size as usize) / DIRENT_SZ;
+            let new_size = (file_count + 1) * DIRENT_SZ;
+            self.increase_size(new_size as u32, root_inode, &mut fs);
+            let dirent = DirEntry::new(name, new_inode_id);
+            root_inode.write_at(file_count * DIRENT_SZ, dirent.as_bytes(), &self.block_device);
+        });
+        self.modify_disk_inode(DiskInode::hard_link_add);
+        child.modify_disk_inode(DiskInode::hard_link_add);
+        block_cache_sync_all();
+        Some(child)
+    }
+    /// Remove the empty child directory named `name`. Refuses with
+    /// `ENOTEMPTY` if anything other than `.`/`..` is still present.
+    pub fn rmdir(&self, name: &str) -> isize {
+        let Some(child) = self.find(name) else {
+            return -1;
+        };
+        if !child.is_dir() {
+            return -1;
+        }
+        let only_dot_entries = child.read_disk_inode(|disk_inode| {
+            let file_count = (disk_inode.size as usize) / DIRENT_SZ;
+            let mut dirent = DirEntry::empty();
+            (0..file_count).all(|i| {
+                disk_inode.read_at(i * DIRENT_SZ, dirent.as_bytes_mut(), &self.block_device);
+                dirent.name() == "." || dirent.name() == ".." || dirent.name().is_empty()
+            })
+        });
+        if !only_dot_entries {
+            return ENOTEMPTY;
+        }
+        self.modify_disk_inode(|root_inode| {
+            let file_count = (root_inode.size as usize) / DIRENT_SZ;
+            let mut dirent = DirEntry::empty();
+            for i in 0..file_count {
+                root_inode.read_at(i * DIRENT_SZ, dirent.as_bytes_mut(), &self.block_device);
+                if dirent.name() == name {
+                    let empty = DirEntry::empty();
+                    root_inode.write_at(i * DIRENT_SZ, empty.as_bytes(), &self.block_device);
+                    break;
+                }
+            }
+        });
+        self.modify_disk_inode(DiskInode::hard_link_del);
+        child.clear();
+        block_cache_sync_all();
+        0
+    }
+    /// Query live capacity/free-space figures for the filesystem this
+    /// inode belongs to.
+    pub fn statfs(&self) -> Statfs {
+        self.fs.lock().statfs()
+    }
+}
+
+/// Split a path like `/a/b/c` into its non-empty components, mirroring
+/// the `rsplit_path`/`user_path_at` helpers in the referenced VFS. `.` and
+/// `..` are kept as ordinary components, looked up like any other name
+/// (through the `.`/`..` dirents `mkdir` writes; the root has none). A
+/// leading `/` is ignored, callers resolve relative to whatever root
+/// `Inode` they start from (the filesystem root, or a process's cwd).
+pub fn split_path(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+/// `mode` bit for "this inode is a directory" (`S_IFDIR`), mirroring the
+/// standard POSIX `st_mode` type field.
+pub const S_IFDIR: u16 = 0o040000;
+/// `mode` bit for "this inode is a regular file" (`S_IFREG`).
+pub const S_IFREG: u16 = 0o100000;
+
+/// Which access class (owner/group/other) is being tested.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum AccessClass {
+    Owner,
+    Group,
+    Other,
+}
+
+impl AccessClass {
+    /// Bit shift into `mode` for this class's `rwx` triple (owner is the
+    /// high triple, other is the low triple, mirroring `chmod` octal
+    /// digits).
+    fn shift(self) -> u16 {
+        match self {
+            AccessClass::Owner => 6,
+            AccessClass::Group => 3,
+            AccessClass::Other => 0,
+        }
+    }
+}
+
+/// Requested access, as a small bitset matching the low 3 bits of a mode
+/// triple: read (4), write (2), execute (1).
+pub const ACCESS_READ: u8 = 0b100;
+/// See [`ACCESS_READ`].
+pub const ACCESS_WRITE: u8 = 0b010;
+/// See [`ACCESS_READ`].
+pub const ACCESS_EXEC: u8 = 0b001;
+
+/// Standard POSIX access-control algorithm: root (`uid == 0`) is granted
+/// everything; otherwise the owner/group/other triple of `mode` that
+/// applies to `(req_uid, req_gid)` is tested against `want`, and access is
+/// denied unless every requested bit is present in that triple.
+pub fn check_access(
+    req_uid: u32,
+    req_gid: u32,
+    inode_uid: u32,
+    inode_gid: u32,
+    mode: u16,
+    want: u8,
+) -> bool {
+    if req_uid == 0 {
+        return true;
+    }
+    let class = if req_uid == inode_uid {
+        AccessClass::Owner
+    } else if req_gid == inode_gid {
+        AccessClass::Group
+    } else {
+        AccessClass::Other
+    };
+    let triple = ((mode >> class.shift()) & 0b111) as u8;
+    (triple & want) == want
 }
\ No newline at end of file
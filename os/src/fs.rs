@@ -0,0 +1,219 @@
+//! File-facing syscall support: open-flag/`Stat` types, a per-task-visible
+//! `OSInode` wrapping an `easy_fs::Inode` with its own read/write cursor,
+//! and the root filesystem every relative path resolves against.
+
+use crate::mm::UserBuffer;
+use crate::sync::UPSafeCell;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use bitflags::bitflags;
+use easy_fs::{BlockDevice, EasyFileSystem, Inode};
+use lazy_static::lazy_static;
+use spin::Mutex;
+
+pub use easy_fs::Statfs;
+
+bitflags! {
+    /// Mirrors the subset of POSIX `open(2)` flags this kernel understands.
+    pub struct OpenFlags: u32 {
+        /// Open for reading only (the default: value `0`).
+        const RDONLY = 0;
+        /// Open for writing only.
+        const WRONLY = 1 << 0;
+        /// Open for reading and writing.
+        const RDWR = 1 << 1;
+        /// Create the file if it doesn't already exist.
+        const CREATE = 1 << 9;
+        /// Truncate an existing file to empty on open.
+        const TRUNC = 1 << 10;
+    }
+}
+
+impl OpenFlags {
+    /// Decode the `(readable, writable)` pair these flags imply.
+    fn read_write(&self) -> (bool, bool) {
+        if self.is_empty() {
+            (true, false)
+        } else if self.contains(Self::WRONLY) {
+            (false, true)
+        } else {
+            (true, true)
+        }
+    }
+}
+
+/// POSIX-`stat`-shaped file status, surfaced through `sys_fstat`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Stat {
+    /// Inode id.
+    pub ino: u64,
+    /// `rwx`-plus-type mode bits.
+    pub mode: u32,
+    /// Hard-link count.
+    pub nlink: u32,
+    /// Owning user id.
+    pub uid: u32,
+    /// Owning group id.
+    pub gid: u32,
+    /// File size in bytes.
+    pub size: u64,
+}
+
+struct OSInodeInner {
+    offset: usize,
+    inode: Arc<Inode>,
+}
+
+/// A process's view of an open file: the shared `easy_fs::Inode` plus this
+/// file descriptor's own read/write cursor and access mode.
+pub struct OSInode {
+    readable: bool,
+    writable: bool,
+    inner: UPSafeCell<OSInodeInner>,
+}
+
+impl OSInode {
+    /// Wrap `inode` as a freshly opened file descriptor at offset 0.
+    pub fn new(readable: bool, writable: bool, inode: Arc<Inode>) -> Self {
+        Self {
+            readable,
+            writable,
+            inner: unsafe { UPSafeCell::new(OSInodeInner { offset: 0, inode }) },
+        }
+    }
+    /// Whether this descriptor was opened for reading.
+    pub fn readable(&self) -> bool {
+        self.readable
+    }
+    /// Whether this descriptor was opened for writing.
+    pub fn writable(&self) -> bool {
+        self.writable
+    }
+    /// Read the whole file from the start, regardless of the cursor.
+    pub fn read_all(&self) -> Vec<u8> {
+        let inner = self.inner.exclusive_access();
+        let mut buf = [0u8; 512];
+        let mut data = Vec::new();
+        let mut offset = 0usize;
+        loop {
+            let len = inner.inode.read_at(offset, &mut buf);
+            if len == 0 {
+                break;
+            }
+            offset += len;
+            data.extend_from_slice(&buf[..len]);
+        }
+        data
+    }
+    /// Read into `buf`'s fragments from the current cursor, advancing it by
+    /// however many bytes were actually read.
+    pub fn read(&self, mut buf: UserBuffer) -> usize {
+        let mut inner = self.inner.exclusive_access();
+        let mut total = 0usize;
+        for fragment in buf.buffers.iter_mut() {
+            let len = inner.inode.read_at(inner.offset, fragment);
+            if len == 0 {
+                break;
+            }
+            inner.offset += len;
+            total += len;
+            if len < fragment.len() {
+                break;
+            }
+        }
+        total
+    }
+    /// Write `buf`'s fragments from the current cursor, advancing it by
+    /// however many bytes were actually written.
+    pub fn write(&self, buf: UserBuffer) -> usize {
+        let mut inner = self.inner.exclusive_access();
+        let mut total = 0usize;
+        for fragment in buf.buffers.iter() {
+            let len = inner.inode.write_at(inner.offset, fragment);
+            inner.offset += len;
+            total += len;
+        }
+        total
+    }
+    /// This descriptor's current `Stat`.
+    pub fn stat(&self) -> Stat {
+        let inner = self.inner.exclusive_access();
+        Stat {
+            ino: inner.inode.find_inode_id_by_pos() as u64,
+            mode: inner.inode.mode() as u32,
+            nlink: inner.inode.get_hard_link_num(),
+            uid: inner.inode.uid(),
+            gid: inner.inode.gid(),
+            size: inner.inode.size(),
+        }
+    }
+}
+
+/// Open the file at `path` (resolved against the root filesystem),
+/// optionally creating it if `flags` asks for that and it doesn't exist.
+pub fn open_file(path: &str, flags: OpenFlags) -> Option<Arc<OSInode>> {
+    let (readable, writable) = flags.read_write();
+    match ROOT_INODE.resolve_path(path) {
+        Ok(Some(inode)) => {
+            if flags.contains(OpenFlags::TRUNC) {
+                inode.clear();
+            }
+            Some(Arc::new(OSInode::new(readable, writable, inode)))
+        }
+        Ok(None) if flags.contains(OpenFlags::CREATE) => {
+            let (parent_path, name) = match path.rsplit_once('/') {
+                Some((parent, name)) => (parent, name),
+                None => ("", path),
+            };
+            let parent = match ROOT_INODE.resolve_path(parent_path) {
+                Ok(Some(parent)) => parent,
+                _ => return None,
+            };
+            parent
+                .create(name, 0, 0, 0o644)
+                .map(|inode| Arc::new(OSInode::new(readable, writable, inode)))
+        }
+        _ => None,
+    }
+}
+
+/// In-memory placeholder for a real block device: no virtio (or other)
+/// block driver exists anywhere in this tree, so [`ROOT_INODE`] is backed
+/// by a `Vec` of zeroed blocks that's reformatted fresh on every boot
+/// instead of a persistent disk image. Swap this out once a real driver is
+/// wired in; nothing above `easy_fs::BlockDevice` needs to change.
+struct MemBlockDevice {
+    blocks: Mutex<Vec<[u8; easy_fs::BLOCK_SZ]>>,
+}
+
+impl MemBlockDevice {
+    fn new(block_count: usize) -> Self {
+        Self {
+            blocks: Mutex::new(alloc::vec![[0u8; easy_fs::BLOCK_SZ]; block_count]),
+        }
+    }
+}
+
+impl BlockDevice for MemBlockDevice {
+    fn read_block(&self, block_id: usize, buf: &mut [u8]) {
+        buf.copy_from_slice(&self.blocks.lock()[block_id]);
+    }
+    fn write_block(&self, block_id: usize, buf: &[u8]) {
+        self.blocks.lock()[block_id].copy_from_slice(buf);
+    }
+}
+
+/// Total blocks in the placeholder image (4 MiB at `BLOCK_SZ = 512`).
+const FS_BLOCKS: u32 = 8192;
+/// Blocks dedicated to the inode bitmap.
+const FS_INODE_BITMAP_BLOCKS: u32 = 1;
+
+lazy_static! {
+    /// The filesystem root every relative path resolves against.
+    pub static ref ROOT_INODE: Arc<Inode> = {
+        let block_device = Arc::new(MemBlockDevice::new(FS_BLOCKS as usize)) as Arc<dyn BlockDevice>;
+        let efs = EasyFileSystem::create(block_device, FS_BLOCKS, FS_INODE_BITMAP_BLOCKS);
+        Arc::new(EasyFileSystem::root_inode(&efs))
+    };
+}
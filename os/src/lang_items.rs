@@ -1,9 +1,46 @@
 //! The panic handler
 
 use crate::sbi::shutdown;
+use core::arch::asm;
 use core::panic::PanicInfo;
 use log::*;
 
+extern "C" {
+    /// Exported by `entry.asm`: the lowest address still inside the boot
+    /// stack, used to know when the frame-pointer walk below has left it.
+    fn boot_stack_lower_bound();
+    /// Exported by `entry.asm`: the top of the boot stack.
+    fn boot_stack_top();
+}
+
+/// Walk saved frame pointers from the current `fp`/`ra`, printing each
+/// return address, until `fp` leaves `[boot_stack_lower_bound,
+/// boot_stack_top)` — i.e. until we've unwound past the bottom of the
+/// stack the panic happened on.
+///
+/// # Safety
+/// Relies on every frame on the stack having been compiled with frame
+/// pointers preserved (`-Cforce-frame-pointers`), and on the standard `fp
+/// -> [saved fp, saved ra]` layout at `fp-16`/`fp-8`.
+unsafe fn print_backtrace() {
+    let lower = boot_stack_lower_bound as usize;
+    let upper = boot_stack_top as usize;
+    let mut fp: usize;
+    asm!("mv {}, fp", out(reg) fp);
+    error!("[kernel] backtrace:");
+    while fp >= lower && fp < upper {
+        let ra = *((fp - 8) as *const usize);
+        error!("[kernel]     {:#x}", ra);
+        fp = *((fp - 16) as *const usize);
+    }
+}
+
+/// Reason code passed to the SBI System Reset extension for a panic-driven
+/// shutdown, distinct from the graceful-shutdown code `sbi::shutdown(false)`
+/// uses for passing tests, so CI can tell the two apart from QEMU's exit
+/// status.
+const RESET_REASON_SYSTEM_FAILURE: bool = true;
+
 #[panic_handler] // 用于标记核心库core中的 panic! 宏要对接的函数
 fn panic(info: &PanicInfo) -> ! {
     if let Some(location) = info.location() {
@@ -16,5 +53,8 @@ fn panic(info: &PanicInfo) -> ! {
     } else {
         error!("[kernel] Panicked: {}", info.message().unwrap());
     }
-    shutdown(true)
+    unsafe {
+        print_backtrace();
+    }
+    shutdown(RESET_REASON_SYSTEM_FAILURE)
 }
@@ -0,0 +1,89 @@
+//! Loads a flat user binary into a user address region and runs it from
+//! inside an embassy task rather than a blocking scheduler. The task has no
+//! `.await` point: once polled it runs the process synchronously until
+//! `exit`, handling each `ecall` inline, and its executor runs nothing else
+//! in the meantime.
+
+use crate::console::putchar;
+use crate::trap::UserTrap;
+use core::arch::global_asm;
+
+global_asm!(include_str!("user_trap.S"));
+global_asm!(include_str!("demo_user_app.S"));
+
+/// Where the (single, flat-mapped) user image is loaded for this milestone
+/// — identity-mapped, no paging yet.
+const USER_BASE: usize = 0x8040_0000;
+/// Top of the user stack the loaded program runs on.
+const USER_STACK_TOP: usize = 0x8041_0000;
+
+/// `write` syscall id (RISC-V Linux ABI number, matches the numbering
+/// the rest of this crate's syscalls use).
+const SYS_WRITE: usize = 64;
+/// `exit` syscall id.
+const SYS_EXIT: usize = 93;
+
+/// Copy `image` to [`USER_BASE`], point `sepc` there, and drop into U-mode.
+/// Returns only via a trap (an `ecall`), which `enter_user` itself loops on
+/// until the process calls `exit`.
+///
+/// # Safety
+/// Caller must ensure `image` is a valid, position-independent flat binary
+/// built for `USER_BASE`, and that no other task is currently using the
+/// identity-mapped region around it.
+pub unsafe fn load_and_run(image: &[u8]) -> i32 {
+    core::ptr::copy_nonoverlapping(image.as_ptr(), USER_BASE as *mut u8, image.len());
+    enter_user(USER_BASE, USER_STACK_TOP)
+}
+
+/// Set `sstatus.SPP = User`, `sepc = entry`, `sp = stack_top`, and `sret`.
+/// Loops handling `ecall`s (via [`UserTrap`]) until the process exits.
+unsafe fn enter_user(entry: usize, stack_top: usize) -> i32 {
+    use riscv::register::{sepc, sstatus};
+    sstatus::set_spp(sstatus::SPP::User);
+    sepc::write(entry);
+    loop {
+        let trap: UserTrap = sret_into_user(stack_top);
+        match trap.a7 {
+            SYS_WRITE => {
+                let fd = trap.a0;
+                let buf = trap.a1 as *const u8;
+                let len = trap.a2;
+                if fd == 1 || fd == 2 {
+                    for i in 0..len {
+                        putchar(*buf.add(i));
+                    }
+                }
+                // Resume just past the `ecall` that trapped us, with the
+                // return value of `write` in a0.
+                resume_after_ecall(len as isize);
+            }
+            SYS_EXIT => {
+                return trap.a0 as i32;
+            }
+            other => panic!("unsupported syscall from U-mode: {}", other),
+        }
+    }
+}
+
+/// `sret`s into U-mode at whatever `sepc`/`sstatus` are currently set to,
+/// and returns once a trap (here: only `ecall`) brings us back, decoded
+/// into a [`UserTrap`].
+///
+/// Implemented in `user_trap.S`: this is the async milestone's counterpart
+/// of the classic rCore `__restore`/`__alltraps` pair, except the
+/// "scheduler" resuming us afterwards is this function's caller, an embassy
+/// task, rather than a preemptive `TaskManager`.
+extern "C" {
+    fn sret_into_user(stack_top: usize) -> UserTrap;
+    fn resume_after_ecall(a0: isize) -> !;
+}
+
+/// Spawn `image` as an embassy task that owns its own U-mode process. The
+/// task does not yield while the process runs: [`load_and_run`] handles its
+/// traps synchronously until it exits.
+#[embassy_executor::task(pool_size = 4)]
+pub async fn run_user_process(image: &'static [u8]) {
+    let exit_code = unsafe { load_and_run(image) };
+    crate::println!("[kernel] user process exited with code {}", exit_code);
+}
@@ -14,20 +14,28 @@
 #![feature(panic_info_message)]
 #![feature(type_alias_impl_trait)]
 #![feature(impl_trait_in_assoc_type)]
+#![feature(alloc_error_handler)]
+
+extern crate alloc;
 
 use core::arch::global_asm;
 use log::*;
-use embassy_executor::Executor;
 use embassy_executor::Spawner;
-use static_cell::StaticCell;
 
 #[macro_use] // 使用 #[macro_use] 注解将模块中定义的宏导入到当前作用域中
 mod console;
+mod fs;
 mod lang_items;
+mod loader;
 mod logging;
+mod mm;
 mod sbi;
-
-static EXECUTOR: StaticCell<Executor> = StaticCell::new();
+mod smp;
+mod sync;
+mod syscall;
+mod task;
+mod time;
+mod trap;
 
 global_asm!(include_str!("entry.asm"));
 
@@ -40,15 +48,29 @@ pub fn clear_bss() {
     (sbss as usize..ebss as usize).for_each(|a| unsafe { (a as *mut u8).write_volatile(0) });
 }
 
-/// the rust entry-point of os
+/// the rust entry-point of os, run on the boot hart (hart 0); `entry.asm`
+/// parks every other hart in a WFI loop until [`smp::bringup`] wakes them.
 #[no_mangle]
 pub fn rust_main() -> ! {
-    let executor = EXECUTOR.init(Executor::new());
-    executor.run(|spawner| {
+    unsafe {
+        smp::bringup(secondary_entry as usize);
+    }
+    smp::run_on_this_hart(0, |spawner| {
         spawner.spawn(kernel_start(spawner)).unwrap();
     });
 }
 
+/// Where the SBI HSM extension points every secondary hart: set up that
+/// hart's own stack (done by the asm trampoline calling into here) and run
+/// its own executor.
+#[no_mangle]
+pub extern "C" fn secondary_entry(hartid: usize) -> ! {
+    smp::run_on_this_hart(hartid, |_spawner| {
+        // Secondary harts don't print the banner or own shutdown; they
+        // just sit ready to run whatever `smp::spawn_on` hands them.
+    });
+}
+
 #[embassy_executor::task]
 async fn test(){
     let f1 = async {
@@ -81,6 +103,8 @@ async fn kernel_start(spawner: Spawner) {
     }
     clear_bss();
     logging::init();
+    mm::heap_init();
+    trap::enable_timer_interrupt();
     println!("[kernel] Hello, world!");
     trace!(
         "[kernel] .text [{:#x}, {:#x})",
@@ -101,7 +125,23 @@ async fn kernel_start(spawner: Spawner) {
     );
     error!("[kernel] .bss [{:#x}, {:#x})", sbss as usize, ebss as usize);
     spawner.spawn(test()).unwrap();
+    spawner.spawn(loader::run_user_process(demo_user_image())).unwrap();
     // CI autotest success: sbi::shutdown(false)
     // CI autotest failed : sbi::shutdown(true)
     sbi::shutdown(false)
+}
+
+/// The flat U-mode image embedded via `loader.rs`'s `demo_user_app.S`,
+/// standing in until a real user-space build pipeline exists.
+fn demo_user_image() -> &'static [u8] {
+    extern "C" {
+        fn sdemo_user_app();
+        fn edemo_user_app();
+    }
+    unsafe {
+        core::slice::from_raw_parts(
+            sdemo_user_app as usize as *const u8,
+            edemo_user_app as usize - sdemo_user_app as usize,
+        )
+    }
 }
\ No newline at end of file
@@ -0,0 +1,82 @@
+//! Minimal virtual/physical address newtypes.
+//!
+//! This kernel doesn't yet implement per-process multi-level page tables
+//! (see `PageTable::translate_va` in `page_table.rs`), so for now every user
+//! address space is identity-mapped: a `VirtAddr` and its backing `PhysAddr`
+//! share the same numeric value. These types exist so the user/kernel copy
+//! helpers (`translated_byte_buffer` and friends) have a real seam to call
+//! through rather than reaching into raw `usize`s, ready to swap in actual
+//! translation once paging lands.
+
+/// Bytes per page.
+pub const PAGE_SIZE: usize = 4096;
+
+/// A virtual address.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct VirtAddr(pub usize);
+
+/// A physical address.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct PhysAddr(pub usize);
+
+/// A physical page number (a [`PhysAddr`] with the in-page offset stripped).
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct PhysPageNum(pub usize);
+
+/// A virtual page number.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct VirtPageNum(pub usize);
+
+impl From<usize> for VirtAddr {
+    fn from(v: usize) -> Self {
+        Self(v)
+    }
+}
+impl From<usize> for PhysAddr {
+    fn from(v: usize) -> Self {
+        Self(v)
+    }
+}
+impl From<VirtAddr> for usize {
+    fn from(v: VirtAddr) -> Self {
+        v.0
+    }
+}
+impl From<PhysAddr> for usize {
+    fn from(v: PhysAddr) -> Self {
+        v.0
+    }
+}
+
+impl VirtAddr {
+    /// Byte offset within this address's page.
+    pub fn page_offset(&self) -> usize {
+        self.0 & (PAGE_SIZE - 1)
+    }
+    /// The page this address falls in.
+    pub fn floor(&self) -> VirtPageNum {
+        VirtPageNum(self.0 / PAGE_SIZE)
+    }
+    /// The page at or after this address.
+    pub fn ceil(&self) -> VirtPageNum {
+        VirtPageNum((self.0 + PAGE_SIZE - 1) / PAGE_SIZE)
+    }
+}
+
+impl PhysAddr {
+    /// Byte offset within this address's page.
+    pub fn page_offset(&self) -> usize {
+        self.0 & (PAGE_SIZE - 1)
+    }
+    /// The page this address falls in.
+    pub fn floor(&self) -> PhysPageNum {
+        PhysPageNum(self.0 / PAGE_SIZE)
+    }
+}
+
+impl VirtPageNum {
+    /// The first byte address of this page.
+    pub fn base(&self) -> VirtAddr {
+        VirtAddr(self.0 * PAGE_SIZE)
+    }
+}
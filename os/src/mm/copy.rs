@@ -0,0 +1,38 @@
+//! Generic, page-fragment-aware helpers for copying typed values across the
+//! user/kernel boundary, replacing the old pattern of `transmute`-ing a
+//! `#[repr(C)]` struct into a byte array and hand-rolling a one-or-two-page
+//! split (which panics whenever the slice happens to straddle a third
+//! fragment).
+
+use super::translated_byte_buffer;
+use core::mem::size_of;
+
+/// Copy `*value` into user memory at `ptr`, byte for byte, across however
+/// many page fragments `translated_byte_buffer` returns. Equivalent to the
+/// `UserBufferWriter` pattern: walk the fragments, track a running offset
+/// into the source bytes, and copy each fragment's worth out of it.
+pub fn write_to_user<T>(token: usize, ptr: *mut T, value: &T) {
+    let bytes =
+        unsafe { core::slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) };
+    let fragments = translated_byte_buffer(token, ptr as *const u8, size_of::<T>());
+    let mut offset = 0;
+    for mut fragment in fragments.into_iter() {
+        let len = fragment.len();
+        fragment.copy_from_slice(&bytes[offset..offset + len]);
+        offset += len;
+    }
+}
+
+/// Copy `size_of::<T>()` bytes out of user memory at `ptr` into a fresh
+/// `T`, across however many page fragments the source spans.
+pub fn read_from_user<T: Copy>(token: usize, ptr: *const T) -> T {
+    let fragments = translated_byte_buffer(token, ptr as *const u8, size_of::<T>());
+    let mut bytes = alloc::vec![0u8; size_of::<T>()];
+    let mut offset = 0;
+    for fragment in fragments.iter() {
+        let len = fragment.len();
+        bytes[offset..offset + len].copy_from_slice(fragment);
+        offset += len;
+    }
+    unsafe { (bytes.as_ptr() as *const T).read_unaligned() }
+}
@@ -0,0 +1,38 @@
+//! A global heap allocator for the `#![no_std]` kernel, so `extern crate
+//! alloc` (and therefore `Box`/`Vec`-based structures like the timer queue
+//! and process table) actually work.
+//!
+//! Backed by a simple linked-list, size-classed free-list allocator over a
+//! static region reserved in `.bss` (zeroed for free by `clear_bss`).
+
+use buddy_system_allocator::LockedHeap;
+
+/// How much of `.bss` to reserve for the kernel heap.
+const HEAP_SIZE: usize = 8 * 1024 * 1024;
+
+/// The reserved heap region itself, placed in `.bss` so `clear_bss` zeroes
+/// it before `init` ever hands any of it out.
+static mut HEAP_SPACE: [u8; HEAP_SIZE] = [0; HEAP_SIZE];
+
+#[global_allocator]
+static HEAP_ALLOCATOR: LockedHeap<32> = LockedHeap::empty();
+
+/// Hand the reserved region to the allocator. Must run once, early in
+/// `kernel_start`, after `clear_bss`/`logging::init` and before anything
+/// touches `alloc::{boxed::Box, vec::Vec, ...}`.
+pub fn init() {
+    unsafe {
+        HEAP_ALLOCATOR
+            .lock()
+            .init(HEAP_SPACE.as_ptr() as usize, HEAP_SIZE);
+    }
+}
+
+/// Log the failed allocation before giving up — an allocation failure this
+/// early is unrecoverable, but at least CI/ a developer can see what size
+/// class exhausted the heap.
+#[alloc_error_handler]
+fn alloc_error_handler(layout: core::alloc::Layout) -> ! {
+    log::error!("[kernel] heap allocation failed: {:?}", layout);
+    crate::sbi::shutdown(true)
+}
@@ -0,0 +1,12 @@
+//! Memory-management support: the user/kernel copy helpers used throughout
+//! `syscall`, and the global heap allocator `alloc` needs.
+
+mod address;
+mod copy;
+mod heap;
+mod page_table;
+
+pub use address::{PhysAddr, PhysPageNum, VirtAddr, VirtPageNum, PAGE_SIZE};
+pub use copy::{read_from_user, write_to_user};
+pub use heap::init as heap_init;
+pub use page_table::{translated_byte_buffer, translated_refmut, translated_str, PageTable, UserBuffer};
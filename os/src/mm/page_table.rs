@@ -0,0 +1,103 @@
+//! User/kernel address translation and the byte-buffer helpers built on it.
+//!
+//! See `address.rs` for why [`PageTable::translate_va`] is an identity
+//! mapping for now rather than a real multi-level page-table walk.
+
+use super::{PhysAddr, VirtAddr, PAGE_SIZE};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Stands in for a process's page table until real paging lands: today
+/// every `token` (would-be `satp`) maps a virtual address straight onto the
+/// physical address of the same value.
+pub struct PageTable {
+    _token: usize,
+}
+
+impl PageTable {
+    /// Build the translator for address space `token`.
+    pub fn from_token(token: usize) -> Self {
+        Self { _token: token }
+    }
+    /// Translate `va` to its backing physical address.
+    pub fn translate_va(&self, va: VirtAddr) -> Option<PhysAddr> {
+        Some(PhysAddr(va.0))
+    }
+}
+
+/// Split the `len` bytes starting at `ptr` in address space `token` into
+/// page-aligned fragments, each a direct `&mut [u8]` onto the backing
+/// physical memory.
+pub fn translated_byte_buffer(token: usize, ptr: *const u8, len: usize) -> Vec<&'static mut [u8]> {
+    let table = PageTable::from_token(token);
+    let mut start = ptr as usize;
+    let end = start + len;
+    let mut fragments = Vec::new();
+    while start < end {
+        let start_va = VirtAddr::from(start);
+        let vpn = start_va.floor();
+        let pa = table.translate_va(start_va).unwrap();
+        let next_page_start = VirtAddr((vpn.0 + 1) * PAGE_SIZE);
+        let end_usize = next_page_start.0.min(end);
+        let frag_len = end_usize - start;
+        unsafe {
+            fragments.push(core::slice::from_raw_parts_mut(pa.0 as *mut u8, frag_len));
+        }
+        start = end_usize;
+    }
+    fragments
+}
+
+/// Copy a NUL-terminated C string out of user memory at `ptr`.
+pub fn translated_str(token: usize, ptr: *const u8) -> String {
+    let table = PageTable::from_token(token);
+    let mut string = String::new();
+    let mut va = ptr as usize;
+    loop {
+        let pa = table.translate_va(VirtAddr::from(va)).unwrap();
+        let ch = unsafe { *(pa.0 as *const u8) };
+        if ch == 0 {
+            break;
+        }
+        string.push(ch as char);
+        va += 1;
+    }
+    string
+}
+
+/// Get a mutable reference to `T` at `ptr` in user memory (assumes `T`
+/// doesn't straddle a page boundary).
+pub fn translated_refmut<T>(token: usize, ptr: *mut T) -> &'static mut T {
+    let table = PageTable::from_token(token);
+    let va = VirtAddr::from(ptr as usize);
+    let pa = table.translate_va(va).unwrap();
+    unsafe { &mut *(pa.0 as *mut T) }
+}
+
+/// A scatter/gather view over several translated fragments of a single
+/// user-space buffer, used by `sys_read`/`sys_write`/`sys_readlink`.
+pub struct UserBuffer {
+    /// The translated fragments backing this buffer, in order.
+    pub buffers: Vec<&'static mut [u8]>,
+}
+
+impl UserBuffer {
+    /// Wrap the fragments `translated_byte_buffer` returned.
+    pub fn new(buffers: Vec<&'static mut [u8]>) -> Self {
+        Self { buffers }
+    }
+    /// Copy as much of `data` as fits into this buffer, returning the number
+    /// of bytes actually copied.
+    pub fn write(&mut self, data: &[u8]) -> usize {
+        let mut copied = 0;
+        for buffer in self.buffers.iter_mut() {
+            if copied == data.len() {
+                break;
+            }
+            let len = buffer.len().min(data.len() - copied);
+            buffer[..len].copy_from_slice(&data[copied..copied + len]);
+            copied += len;
+        }
+        copied
+    }
+}
@@ -0,0 +1,94 @@
+//! Multi-core bring-up: wakes every secondary hart via the SBI HSM
+//! extension and gives each core its own [`embassy_executor::Executor`],
+//! turning the previously uniprocessor LibOS into a genuine multi-core
+//! async runtime.
+
+use core::arch::global_asm;
+use embassy_executor::{Executor, Spawner};
+use static_cell::StaticCell;
+
+global_asm!(include_str!("smp_entry.S"));
+
+/// Upper bound on the number of harts this kernel will bring up. QEMU's
+/// `virt` machine and the boards this crate targets stay well under this.
+/// Must match `smp_entry.S`'s own `MAX_HARTS`.
+pub const MAX_HARTS: usize = 8;
+
+/// One executor per hart, each in its own cell so cores never contend on a
+/// shared allocation while bringing up.
+static EXECUTORS: [StaticCell<Executor>; MAX_HARTS] = [const { StaticCell::new() }; MAX_HARTS];
+
+/// Per-hart `Spawner`s, published once that hart's executor starts running
+/// so [`spawn_on`] can hand work to a specific core.
+static SPAWNERS: [StaticCell<Spawner>; MAX_HARTS] = [const { StaticCell::new() }; MAX_HARTS];
+
+/// SBI HSM extension id, per the SBI spec.
+const SBI_EXT_HSM: usize = 0x48534D;
+/// `sbi_hart_start` function id within the HSM extension.
+const SBI_HSM_HART_START: usize = 0;
+
+/// Ask SBI to start `hartid` executing at `entry`, with `opaque` handed to
+/// it verbatim in `a1`.
+fn sbi_hart_start(hartid: usize, entry: usize, opaque: usize) -> isize {
+    let ret: isize;
+    unsafe {
+        core::arch::asm!(
+            "ecall",
+            in("a0") hartid,
+            in("a1") entry,
+            in("a2") opaque,
+            in("a6") SBI_HSM_HART_START,
+            in("a7") SBI_EXT_HSM,
+            lateout("a0") ret,
+        );
+    }
+    ret
+}
+
+extern "C" {
+    /// Defined in `smp_entry.S`: gives the waking hart a stack of its own
+    /// (SBI hands control to `hart_start`'s `entry` with no stack set up at
+    /// all, unlike the boot hart's `entry.asm` prologue) before jumping to
+    /// the real entry point passed through in `a1`.
+    fn hart_entry_trampoline();
+}
+
+/// Wake every hart other than the boot hart (hart 0), routing each through
+/// `hart_entry_trampoline` (for its own stack) to `secondary_entry`.
+///
+/// # Safety
+/// Must only be called once, from the boot hart, before any other hart has
+/// been started.
+pub unsafe fn bringup(secondary_entry: usize) {
+    for hartid in 1..MAX_HARTS {
+        sbi_hart_start(hartid, hart_entry_trampoline as usize, secondary_entry);
+    }
+}
+
+/// Per-hart init: run hart `hartid`'s own `Executor`, publishing its
+/// `Spawner` for [`spawn_on`] once it's ready.
+///
+/// Also establishes the `tp`-holds-hart-id convention `user_trap.S` indexes
+/// its per-hart trap state by, since this is the one place both the boot
+/// hart (via `rust_main`) and every secondary hart (via `secondary_entry`)
+/// are guaranteed to pass through before any embassy task can run.
+pub fn run_on_this_hart(hartid: usize, spawner_for_boot_task: impl FnOnce(Spawner)) -> ! {
+    unsafe {
+        core::arch::asm!("mv tp, {0}", in(reg) hartid);
+    }
+    let executor = EXECUTORS[hartid].init(Executor::new());
+    executor.run(|spawner| {
+        SPAWNERS[hartid].init(spawner);
+        spawner_for_boot_task(spawner);
+    });
+}
+
+/// Spawn `token` (the result of `some_task(args)`) on a specific hart's
+/// executor. Panics if that hart hasn't published its `Spawner` yet (i.e.
+/// hasn't finished [`run_on_this_hart`]'s startup).
+pub fn spawn_on(hartid: usize, token: impl embassy_executor::SpawnToken<impl Sized>) {
+    let spawner: &Spawner = SPAWNERS[hartid]
+        .try_get()
+        .expect("hart has not published its Spawner yet");
+    spawner.spawn(token).ok();
+}
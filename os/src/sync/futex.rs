@@ -0,0 +1,107 @@
+//! Kernel-side support for `sys_futex`: userspace locks built on a plain
+//! integer in the caller's own address space, rather than a kernel handle
+//! per lock.
+//!
+//! Waiters are keyed by the *physical* location of the futex word (page
+//! number + in-page offset) rather than the virtual address, so two
+//! processes (or two threads with different `satp`) that happen to share
+//! the underlying page still see the same key.
+
+use crate::sync::UPSafeCell;
+use crate::task::TaskControlBlock;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use lazy_static::*;
+
+/// Wake every waiter regardless of bitset, the default `val3` for plain
+/// `FUTEX_WAIT`/`FUTEX_WAKE`.
+pub const FUTEX_BITSET_MATCH_ANY: u32 = 0xffff_ffff;
+
+/// `op` values understood by [`sys_futex`](crate::syscall::sys_futex).
+pub const FUTEX_WAIT: usize = 0;
+/// Wake at most `val` waiters.
+pub const FUTEX_WAKE: usize = 1;
+/// `FUTEX_WAIT` with an explicit wake bitset in `val3`.
+pub const FUTEX_WAIT_BITSET: usize = 9;
+/// `FUTEX_WAKE` with an explicit wake bitset in `val3`.
+pub const FUTEX_WAKE_BITSET: usize = 10;
+
+/// Identifies a futex word by the physical page it lives on plus the
+/// byte offset within that page, so the key is stable across translations.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct FutexKey {
+    ppn: usize,
+    offset: usize,
+}
+
+impl FutexKey {
+    /// Build a key from a physical page number and in-page byte offset.
+    pub fn new(ppn: usize, offset: usize) -> Self {
+        Self { ppn, offset }
+    }
+}
+
+struct Waiter {
+    task: Arc<TaskControlBlock>,
+    bitset: u32,
+}
+
+struct FutexBucket {
+    waiters: Vec<Waiter>,
+}
+
+impl FutexBucket {
+    fn new() -> Self {
+        Self {
+            waiters: Vec::new(),
+        }
+    }
+}
+
+lazy_static! {
+    /// All futex wait queues in the kernel, keyed by the physical location
+    /// of the futex word.
+    static ref FUTEX_TABLE: UPSafeCell<BTreeMap<FutexKey, FutexBucket>> =
+        unsafe { UPSafeCell::new(BTreeMap::new()) };
+}
+
+/// Register `task` as blocked on the futex identified by `key`, tagged with
+/// `bitset`. The caller must have already re-verified `*uaddr == val` under
+/// the same lock that protects the wait-queue insertion, so there is no
+/// lost-wakeup window between the check and going to sleep.
+pub fn futex_wait(key: FutexKey, bitset: u32, task: Arc<TaskControlBlock>) {
+    let mut table = FUTEX_TABLE.exclusive_access();
+    let bucket = table.entry(key).or_insert_with(FutexBucket::new);
+    bucket.waiters.push(Waiter { task, bitset });
+}
+
+/// Remove `task` from the wait queue for `key` without waking it, used when
+/// a registered timeout fires before a matching `FUTEX_WAKE` arrives.
+pub fn futex_remove(key: FutexKey, task: &Arc<TaskControlBlock>) {
+    let mut table = FUTEX_TABLE.exclusive_access();
+    if let Some(bucket) = table.get_mut(&key) {
+        bucket.waiters.retain(|w| !Arc::ptr_eq(&w.task, task));
+    }
+}
+
+/// Wake up to `max_wakeups` tasks waiting on `key` whose bitset ANDs
+/// non-zero with `wake_bitset`, returning how many were actually woken.
+pub fn futex_wake(key: FutexKey, max_wakeups: usize, wake_bitset: u32) -> usize {
+    let mut table = FUTEX_TABLE.exclusive_access();
+    let Some(bucket) = table.get_mut(&key) else {
+        return 0;
+    };
+    let mut woken = 0;
+    let mut remaining = Vec::new();
+    for waiter in bucket.waiters.drain(..) {
+        if woken < max_wakeups && (waiter.bitset & wake_bitset) != 0 {
+            crate::task::wakeup_task(waiter.task);
+            woken += 1;
+        } else {
+            remaining.push(waiter);
+        }
+    }
+    bucket.waiters = remaining;
+    woken
+}
@@ -0,0 +1,38 @@
+//! Shared low-level synchronization primitives: [`UPSafeCell`] (uniprocessor
+//! interior mutability, safe as long as every access happens with a single
+//! hart holding the critical section at a time) plus the higher-level
+//! primitives layered on top of it.
+
+pub mod futex;
+
+use core::cell::{Ref, RefCell, RefMut};
+
+/// Wraps `T` in a `RefCell` and unsafely asserts `Sync`, which is sound as
+/// long as accesses never race (true of every kernel-mode critical section
+/// in this crate).
+pub struct UPSafeCell<T> {
+    inner: RefCell<T>,
+}
+
+unsafe impl<T> Sync for UPSafeCell<T> {}
+
+impl<T> UPSafeCell<T> {
+    /// Wrap `value`.
+    ///
+    /// # Safety
+    /// The caller must ensure accesses never race (see the type's doc
+    /// comment).
+    pub unsafe fn new(value: T) -> Self {
+        Self {
+            inner: RefCell::new(value),
+        }
+    }
+    /// Shared access.
+    pub fn access(&self) -> Ref<'_, T> {
+        self.inner.borrow()
+    }
+    /// Exclusive access.
+    pub fn exclusive_access(&self) -> RefMut<'_, T> {
+        self.inner.borrow_mut()
+    }
+}
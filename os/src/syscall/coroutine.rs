@@ -0,0 +1,34 @@
+//! `sys_spawn_coroutine`/`sys_coroutine_yield`: a cheap cooperative
+//! concurrency tier for I/O-bound fan-out, lighter than spawning a kernel
+//! thread per task because coroutines of the same process share its page
+//! table.
+use crate::task::coroutine::{spawn_coroutine, yield_coroutine};
+use crate::task::{block_current_and_run_next, current_task};
+
+/// Register a stackless coroutine in the caller's per-process executor
+/// ready queue. `entry` is the coroutine's entry point, `arg` is passed to
+/// it in `a0` on first resume.
+pub fn sys_spawn_coroutine(entry: usize, arg: usize) -> isize {
+    trace!(
+        "kernel:pid[{}] sys_spawn_coroutine entry={:#x}",
+        current_task().unwrap().pid.0,
+        entry
+    );
+    let pid = current_task().unwrap().pid.0;
+    spawn_coroutine(pid, entry, arg) as isize
+}
+
+/// Cooperatively hand control to the next ready coroutine of the calling
+/// process. If none are ready, every coroutine is blocked, so fall back to
+/// parking the underlying kernel thread instead of busy-looping.
+pub fn sys_coroutine_yield() -> isize {
+    trace!(
+        "kernel:pid[{}] sys_coroutine_yield",
+        current_task().unwrap().pid.0
+    );
+    let pid = current_task().unwrap().pid.0;
+    if !yield_coroutine(pid) {
+        block_current_and_run_next();
+    }
+    0
+}
@@ -1,7 +1,6 @@
 //! File and filesystem-related syscalls
-use core::mem::{size_of, transmute};
 use crate::fs::{open_file, OpenFlags, Stat};
-use crate::mm::{translated_byte_buffer, translated_str, UserBuffer};
+use crate::mm::{translated_byte_buffer, translated_str, write_to_user, UserBuffer};
 use crate::task::{current_task, current_user_token};
 
 pub fn sys_write(fd: usize, buf: *const u8, len: usize) -> isize {
@@ -76,15 +75,11 @@ pub fn sys_close(fd: usize) -> isize {
     0
 }
 
-/// YOUR JOB: Implement fstat.
 /// 功能：获取文件状态
 /// fd: 文件描述符
 /// st: 文件状态结构体
 pub fn sys_fstat(_fd: usize, _st: *mut Stat) -> isize {
-    trace!(
-        "kernel:pid[{}] sys_fstat NOT IMPLEMENTED",
-        current_task().unwrap().pid.0
-    );
+    trace!("kernel:pid[{}] sys_fstat", current_task().unwrap().pid.0);
     // 根据文件描述符取得文件
     let token = current_user_token();
     let task = current_task().unwrap();
@@ -100,21 +95,7 @@ pub fn sys_fstat(_fd: usize, _st: *mut Stat) -> isize {
         drop(inner);
         // 获取文件状态
         let stat = file.stat();
-        // 获取_st的可写缓存
-        let mut st_buffer = translated_byte_buffer(token, _st as *const u8, size_of::<Stat>());
-        if st_buffer[0].len() >= size_of::<Stat>() {
-            let page_ptr = st_buffer[0].as_mut_ptr() as *mut Stat;
-            unsafe {
-                (*page_ptr) = stat
-            }
-        } else {
-            let available_len = st_buffer[0].len();
-            let stat_bytes: [u8; size_of::<Stat>()] = unsafe {
-                transmute(stat)
-            };
-            st_buffer[0].copy_from_slice(&stat_bytes[..available_len]);
-            st_buffer[1].copy_from_slice(&stat_bytes[available_len..]);
-        }
+        write_to_user(token, _st, &stat);
         return 0;
     }
     -1
@@ -150,4 +131,115 @@ pub fn sys_unlinkat(_name: *const u8) -> isize {
         return inode.del_link(name.as_str(), &root_inode);
     }
     -1
+}
+
+/// Create a symlink named `linkpath` whose contents are the literal
+/// `target` path (not resolved at creation time).
+pub fn sys_symlink(target: *const u8, linkpath: *const u8) -> isize {
+    trace!(
+        "kernel:pid[{}] sys_symlink",
+        current_task().unwrap().pid.0
+    );
+    let token = current_user_token();
+    let target = translated_str(token, target);
+    let linkpath = translated_str(token, linkpath);
+    let root_inode = crate::fs::ROOT_INODE.clone();
+    match root_inode.symlink(linkpath.as_str(), target.as_str()) {
+        Some(_) => 0,
+        None => -1,
+    }
+}
+
+/// Read the target path stored in the symlink at `path` into `buf`
+/// (truncated to `len` bytes), returning the number of bytes written.
+pub fn sys_readlink(path: *const u8, buf: *mut u8, len: usize) -> isize {
+    trace!(
+        "kernel:pid[{}] sys_readlink",
+        current_task().unwrap().pid.0
+    );
+    let token = current_user_token();
+    let path = translated_str(token, path);
+    let root_inode = crate::fs::ROOT_INODE.clone();
+    let Some(inode) = root_inode.find(path.as_str()) else {
+        return -1;
+    };
+    if !inode.is_symlink() {
+        return -1;
+    }
+    let target = inode.read_link();
+    let n = target.len().min(len);
+    let user_buf = crate::mm::UserBuffer::new(crate::mm::translated_byte_buffer(
+        token, buf, n,
+    ));
+    user_buf.write(&target.as_bytes()[..n]);
+    n as isize
+}
+
+/// Create a directory at `path`, resolved against the caller's current
+/// working directory.
+pub fn sys_mkdir(path: *const u8) -> isize {
+    trace!("kernel:pid[{}] sys_mkdir", current_task().unwrap().pid.0);
+    let token = current_user_token();
+    let path = translated_str(token, path);
+    let task = current_task().unwrap();
+    let inner = task.inner_exclusive_access();
+    let cwd = inner.cwd.clone();
+    drop(inner);
+    let (parent_path, name) = match path.rsplit_once('/') {
+        Some((parent, name)) => (parent, name),
+        None => ("", path.as_str()),
+    };
+    let Ok(Some(parent)) = cwd.resolve_path(parent_path) else {
+        return -1;
+    };
+    match parent.mkdir(name, 0, 0, 0o755) {
+        Some(_) => 0,
+        None => -1,
+    }
+}
+
+/// Change the caller's current working directory to `path`.
+pub fn sys_chdir(path: *const u8) -> isize {
+    trace!("kernel:pid[{}] sys_chdir", current_task().unwrap().pid.0);
+    let token = current_user_token();
+    let path = translated_str(token, path);
+    let task = current_task().unwrap();
+    let mut inner = task.inner_exclusive_access();
+    let cwd = inner.cwd.clone();
+    match cwd.resolve_path(path.as_str()) {
+        Ok(Some(target)) if target.is_dir() => {
+            inner.cwd = target;
+            0
+        }
+        _ => -1,
+    }
+}
+
+/// Remove the empty directory at `path` (refuses with `ENOTEMPTY` if it
+/// still has entries besides `.`/`..`).
+pub fn sys_rmdir(path: *const u8) -> isize {
+    trace!("kernel:pid[{}] sys_rmdir", current_task().unwrap().pid.0);
+    let token = current_user_token();
+    let path = translated_str(token, path);
+    let task = current_task().unwrap();
+    let inner = task.inner_exclusive_access();
+    let cwd = inner.cwd.clone();
+    drop(inner);
+    let (parent_path, name) = match path.rsplit_once('/') {
+        Some((parent, name)) => (parent, name),
+        None => ("", path.as_str()),
+    };
+    let Ok(Some(parent)) = cwd.resolve_path(parent_path) else {
+        return -1;
+    };
+    parent.rmdir(name)
+}
+
+/// Report total/free data blocks and inodes for the root filesystem.
+pub fn sys_statfs(_buf: *mut crate::fs::Statfs) -> isize {
+    trace!("kernel:pid[{}] sys_statfs", current_task().unwrap().pid.0);
+    let token = current_user_token();
+    let statfs = crate::fs::ROOT_INODE.statfs();
+    write_to_user(token, _buf, &statfs);
+    0
 }
\ No newline at end of file
@@ -24,16 +24,54 @@ const SYSCALL_MUNMAP: usize = 215;
 const SYSCALL_MMAP: usize = 222;
 /// taskinfo syscall
 const SYSCALL_TASK_INFO: usize = 410;
+/// kill syscall
+const SYSCALL_KILL: usize = 129;
+/// sigaction syscall
+const SYSCALL_SIGACTION: usize = 134;
+/// sigprocmask syscall
+const SYSCALL_SIGPROCMASK: usize = 135;
+/// sigreturn syscall
+const SYSCALL_SIGRETURN: usize = 139;
+/// futex syscall
+const SYSCALL_FUTEX: usize = 98;
+/// sched_setscheduler syscall
+const SYSCALL_SCHED_SETSCHEDULER: usize = 119;
+/// sched_getscheduler syscall
+const SYSCALL_SCHED_GETSCHEDULER: usize = 120;
+/// spawn_coroutine syscall
+const SYSCALL_SPAWN_COROUTINE: usize = 450;
+/// coroutine_yield syscall
+const SYSCALL_COROUTINE_YIELD: usize = 451;
+/// symlinkat syscall
+const SYSCALL_SYMLINKAT: usize = 36;
+/// readlinkat syscall
+const SYSCALL_READLINKAT: usize = 78;
+/// mkdirat syscall
+const SYSCALL_MKDIRAT: usize = 34;
+/// chdir syscall
+const SYSCALL_CHDIR: usize = 49;
+/// rmdir/unlinkat(AT_REMOVEDIR) syscall, kept distinct for clarity here
+const SYSCALL_RMDIR: usize = 35;
+/// statfs syscall
+const SYSCALL_STATFS: usize = 43;
 
+mod coroutine;
 mod fs;
 mod process;
+mod sched;
+mod signal;
+mod sync;
 
+use coroutine::*;
 use fs::*;
 use process::*;
+use sched::*;
+use signal::*;
+use sync::sys_futex;
 use lazy_static::*;
 use crate::{
     sync::UPSafeCell,
-    task::TASK_MANAGER,
+    task::current_task,
     timer::get_time_ms,
     config::MAX_APP_NUM,
 };
@@ -65,10 +103,10 @@ lazy_static! {
 }
 
 /// handle syscall exception with `syscall_id` and other arguments
-pub fn syscall(syscall_id: usize, args: [usize; 3]) -> isize {
+pub fn syscall(syscall_id: usize, args: [usize; 6]) -> isize {
     // 初始化任务系统调用次数的信息
     let mut task_infos = TASK_INFOLIST.task_infos.exclusive_access();
-    let current_id = TASK_MANAGER.get_current_id();
+    let current_id = current_task().unwrap().pid.0;
     // 更新任务距离第一次调用的时间
     task_infos[current_id].change_time(get_time_ms(), current_id);
 
@@ -102,6 +140,30 @@ pub fn syscall(syscall_id: usize, args: [usize; 3]) -> isize {
         SYSCALL_MMAP => sys_mmap(args[0], args[1], args[2]),
         SYSCALL_MUNMAP => sys_munmap(args[0], args[1]),
         SYSCALL_SBRK => sys_sbrk(args[0] as i32),
+        SYSCALL_KILL => sys_kill(args[0], args[1]),
+        SYSCALL_SIGACTION => sys_sigaction(args[0], args[1], args[2] as *mut usize),
+        SYSCALL_SIGPROCMASK => sys_sigprocmask(args[0] as u32),
+        SYSCALL_SIGRETURN => sys_sigreturn(),
+        SYSCALL_FUTEX => sys_futex(
+            args[0],
+            args[1],
+            args[2] as u32,
+            args[3],
+            args[4],
+            args[5] as u32,
+        ),
+        SYSCALL_SCHED_SETSCHEDULER => {
+            sys_sched_setscheduler(args[0], args[1], args[2] as isize)
+        }
+        SYSCALL_SCHED_GETSCHEDULER => sys_sched_getscheduler(args[0]),
+        SYSCALL_SPAWN_COROUTINE => sys_spawn_coroutine(args[0], args[1]),
+        SYSCALL_COROUTINE_YIELD => sys_coroutine_yield(),
+        SYSCALL_SYMLINKAT => sys_symlink(args[0] as *const u8, args[1] as *const u8),
+        SYSCALL_READLINKAT => sys_readlink(args[0] as *const u8, args[1] as *mut u8, args[2]),
+        SYSCALL_MKDIRAT => sys_mkdir(args[0] as *const u8),
+        SYSCALL_CHDIR => sys_chdir(args[0] as *const u8),
+        SYSCALL_RMDIR => sys_rmdir(args[0] as *const u8),
+        SYSCALL_STATFS => sys_statfs(args[0] as *mut crate::fs::Statfs),
         _ => panic!("Unsupported syscall_id: {}", syscall_id),
     }
 }
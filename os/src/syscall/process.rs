@@ -1,17 +1,22 @@
 //! Process management syscalls
 //!
-use alloc::sync::Arc;
-use core::mem::{size_of, transmute};
+//! `sys_mmap`/`sys_munmap`/`sys_sbrk` are routed by the dispatcher in
+//! `syscall::mod` but are honest stubs below, because this crate's `mm` is still identity-mapped with no
+//! per-task address space to (un)map (see `mm::address`'s module doc
+//! comment). A prior revision of this file also carried `sys_fork`/
+//! `sys_exec`/`sys_waitpid`/`sys_spawn`, built against `task.fork()`/
+//! `task.exec()`/`TaskControlBlock::new()`/`inner.children`/
+//! `inner.memory_set` that were never added anywhere in `task/` (see its
+//! module doc comment) and never routed by the dispatcher either -- none of
+//! that was reachable from a real syscall, so it's been dropped rather than
+//! left as dead code that can't compile.
 use crate::{
-    config::{MAX_SYSCALL_NUM, PAGE_SIZE},
-    fs::{open_file, OpenFlags},
-    mm::{translated_refmut, translated_str, translated_byte_buffer, VirtAddr, MapPermission},
-    task::{
-        add_task, current_task, current_user_token, exit_current_and_run_next,
-        suspend_current_and_run_next, TaskStatus, TaskControlBlock
-    },
+    config::{BIG_STRIDE, MAX_SYSCALL_NUM, PAGE_SIZE},
+    mm::write_to_user,
+    task::{current_task, current_user_token, exit_current_and_run_next,
+        suspend_current_and_run_next, TaskStatus},
     timer::get_time_us,
-    syscall::TASK_INFOLIST, 
+    syscall::TASK_INFOLIST,
 };
 
 #[repr(C)]
@@ -81,117 +86,24 @@ pub fn sys_getpid() -> isize {
     trace!("kernel: sys_getpid pid:{}", current_task().unwrap().pid.0);
     current_task().unwrap().pid.0 as isize
 }
-/// 创建新进程
-pub fn sys_fork() -> isize {
-    trace!("kernel:pid[{}] sys_fork", current_task().unwrap().pid.0);
-    let current_task = current_task().unwrap();
-    let new_task = current_task.fork();
-    let new_pid = new_task.pid.0;
-    // modify trap context of new_task, because it returns immediately after switching
-    let trap_cx = new_task.inner_exclusive_access().get_trap_cx();
-    // we do not have to move to next instruction since we have done it before
-    // for child process, fork returns 0
-    trap_cx.x[10] = 0;
-    // add new task to scheduler
-    add_task(new_task);
-    new_pid as isize
-}
-/// 切换到指定任务
-pub fn sys_exec(path: *const u8) -> isize {
-    trace!("kernel:pid[{}] sys_exec", current_task().unwrap().pid.0);
-    let token = current_user_token();
-    let path = translated_str(token, path);
-    if let Some(app_inode) = open_file(path.as_str(), OpenFlags::RDONLY) {
-        let all_data = app_inode.read_all();
-        let task = current_task().unwrap();
-        task.exec(all_data.as_slice());
-        0
-    } else {
-        -1
-    }
-}
-
-/// If there is not a child process whose pid is same as given, return -1.
-/// Else if there is a child process but it is still running, return -2.
-pub fn sys_waitpid(pid: isize, exit_code_ptr: *mut i32) -> isize {
-    //trace!("kernel: sys_waitpid");
-    let task = current_task().unwrap();
-    // find a child process
-
-    // ---- access current PCB exclusively
-    let mut inner = task.inner_exclusive_access();
-    if !inner
-        .children
-        .iter()
-        .any(|p| pid == -1 || pid as usize == p.getpid())
-    {
-        return -1;
-        // ---- release current PCB
-    }
-    let pair = inner.children.iter().enumerate().find(|(_, p)| {
-        // ++++ temporarily access child PCB exclusively
-        p.inner_exclusive_access().is_zombie() && (pid == -1 || pid as usize == p.getpid())
-        // ++++ release child PCB
-    });
-    if let Some((idx, _)) = pair {
-        let child = inner.children.remove(idx);
-        // confirm that child will be deallocated after being removed from children list
-        assert_eq!(Arc::strong_count(&child), 1);
-        let found_pid = child.getpid();
-        // ++++ temporarily access child PCB exclusively
-        let exit_code = child.inner_exclusive_access().exit_code;
-        // ++++ release child PCB
-        *translated_refmut(inner.memory_set.token(), exit_code_ptr) = exit_code;
-        found_pid as isize
-    } else {
-        -2
-    }
-    // ---- release current PCB automatically
-}
-
-/// YOUR JOB: get time with second and microsecond
-/// HINT: You might reimplement it with virtual memory management.
-/// HINT: What if [`TimeVal`] is splitted by two pages ?
+/// get time with second and microsecond
 pub fn sys_get_time(_ts: *mut TimeVal, _tz: usize) -> isize {
-    trace!(
-        "kernel:pid[{}] sys_get_time NOT IMPLEMENTED",
-        current_task().unwrap().pid.0
-    );
-    // 尝试将按应用的虚地址指向的缓冲区转换为一组按内核虚地址指向的字节数组切片构成的向量
-    let mut ts_buffer = translated_byte_buffer(current_user_token(), _ts as *const u8, size_of::<TimeVal>());
-    // 计算出正确的时间
+    trace!("kernel:pid[{}] sys_get_time", current_task().unwrap().pid.0);
     let us = get_time_us();
-    let time: TimeVal = TimeVal {
+    let time = TimeVal {
         sec: us / 1_000_000,
         usec: us % 1_000_000,
     };
-    // What if [`TimeVal`] is splitted by two pages ?
-    // 判断是否跨页
-    if ts_buffer[0].len() >= 16 {
-        // 第一页就可以存下time
-        let page_ptr = ts_buffer[0].as_mut_ptr() as *mut TimeVal;
-        unsafe {
-            (*page_ptr) = time;
-        }
-    } else {
-        // 将已经包装好的time转换为以字节为单位的数组
-        let time_bytes: [u8; 16] = unsafe { transmute(time) };
-        let available_len = ts_buffer[0].len();
-        ts_buffer[0].copy_from_slice(&time_bytes[..available_len]);
-        ts_buffer[1].copy_from_slice(&time_bytes[available_len..]);
-    }
+    write_to_user(current_user_token(), _ts, &time);
     0
 }
 
-/// YOUR JOB: Finish sys_task_info to pass testcases
-/// HINT: You might reimplement it with virtual memory management.
-/// HINT: What if [`TaskInfo`] is splitted by two pages ?
+/// Finish sys_task_info to pass testcases
 pub fn sys_task_info(_ti: *mut TaskInfo) -> isize {
     trace!(
-        "kernel:pid[{}] sys_task_info NOT IMPLEMENTED",
+        "kernel:pid[{}] sys_task_info",
         current_task().unwrap().pid.0
     );
-    let mut ti_buffer = translated_byte_buffer(current_user_token(), _ti as *const u8, size_of::<TaskInfo>());
     let task_id = &current_task().unwrap().getpid();
     // 获取不可变引用
     let task_infos = TASK_INFOLIST.task_infos.access();
@@ -199,138 +111,66 @@ pub fn sys_task_info(_ti: *mut TaskInfo) -> isize {
     let info = TaskInfo {
         status: task_infos.get(task_id).unwrap().status,
         syscall_times: task_infos.get(task_id).unwrap().syscall_times,
-        time: task_infos.get(task_id).unwrap().time
+        time: task_infos.get(task_id).unwrap().time,
     };
-    // What if [`TimeVal`] is splitted by two pages ?
-    // 判断是否跨页
-    if ti_buffer[0].len() >= size_of::<TaskInfo>() {
-        // 第一页就可以存下info
-        let page_ptr = ti_buffer[0].as_mut_ptr() as *mut TaskInfo;
-        unsafe {
-            (*page_ptr) = info;
-        }
-    } else {
-        // 将已经包装好的info转换为以字节为单位的数组
-        let available_len = ti_buffer[0].len();
-        let info_bytes: [u8; size_of::<TaskInfo>()] = unsafe { transmute(info) };
-        ti_buffer[0].copy_from_slice(&info_bytes[..available_len]);
-        ti_buffer[1].copy_from_slice(&info_bytes[available_len..]);   
-    }
+    drop(task_infos);
+    write_to_user(current_user_token(), _ti, &info);
     0
 }
 
-/// YOUR JOB: Implement mmap.
+/// Map `len` bytes of anonymous memory at `start` into the caller's address
+/// space with the permissions in `port`.
+///
+/// This crate's `mm` is still identity-mapped (see `mm::address`'s module
+/// doc comment) with no per-task `MemorySet` to carve a new mapping out of,
+/// so there's nothing to actually perform here yet; always fails rather
+/// than claiming a mapping that was never made.
 pub fn sys_mmap(_start: usize, _len: usize, _port: usize) -> isize {
     trace!(
-        "kernel:pid[{}] sys_mmap NOT IMPLEMENTED",
+        "kernel:pid[{}] sys_mmap not supported (no per-task address space yet)",
         current_task().unwrap().pid.0
     );
-    // start 需要映射的虚存起始地址，要求按页对齐
-    // start 没有按页大小对齐
-    if _start % PAGE_SIZE != 0 {
-        return  -1;
-    }
-    // port & !0x7 != 0 (port 其余位必须为0)
-    // port & 0x7 = 0 (这样的内存无意义)
-    if (_port & !0x7 != 0) || (_port & 0x7 == 0) {
-        return -1;
-    }
-    // [start, start + len) 中存在已经被映射的页
-    let start_vpn = VirtAddr::from(_start).floor();
-    let end_vpn = VirtAddr::from(_start + _len).ceil();
-    // let task_control_block = TASK_MANAGER.get_task_control_block(TASK_MANAGER.get_current_id());
-    // 现在可以直接获取任务控制块
-    // 获取中间值
-    let task_control_block = current_task().unwrap();
-    // 获取inner
-    let mut task_control_block_inner = task_control_block.inner_exclusive_access();
-    if task_control_block_inner.is_overlap(start_vpn, end_vpn) {
+    if _start % PAGE_SIZE != 0 || (_port & !0x7 != 0) || (_port & 0x7 == 0) {
         return -1;
     }
-    // 参数检查结束，开始分配空间
-    // U模式有效    
-    let per = MapPermission::from_bits((_port as u8) << 1).unwrap() | MapPermission::U;
-    task_control_block_inner.insert_frame(_start, _start + _len, per);
-    drop(task_control_block_inner);
-    0
+    -1
 }
 
-/// YOUR JOB: Implement munmap.
+/// Unmap `len` bytes starting at `start` from the caller's address space.
+/// See [`sys_mmap`]: there's no per-task address space to unmap from yet.
 pub fn sys_munmap(_start: usize, _len: usize) -> isize {
     trace!(
-        "kernel:pid[{}] sys_munmap NOT IMPLEMENTED",
+        "kernel:pid[{}] sys_munmap not supported (no per-task address space yet)",
         current_task().unwrap().pid.0
     );
-    // start 需要映射的虚存起始地址，要求按页对齐
-    // start 没有按页大小对齐
     if _start % PAGE_SIZE != 0 {
-        return  -1;
-    }
-    // [start, start + len) 中存在未被映射的虚存
-    let start_vpn = VirtAddr::from(_start).floor();
-    let end_vpn = VirtAddr::from(_start + _len).ceil();
-    // 获取中间值
-    let task_control_block = current_task().unwrap();
-    // 获取inner
-    let mut task_control_block_inner = task_control_block.inner_exclusive_access();
-    // 不存在未被映射的虚存
-    if task_control_block_inner.memory_set.is_all_exist(start_vpn, end_vpn) {
-        // 这片区域的虚存都存在，取消映射
-        task_control_block_inner.memory_set.mem_set_unmap(start_vpn, end_vpn);
-        return 0;
+        return -1;
     }
     -1
 }
 
-/// change data segment size
-pub fn sys_sbrk(size: i32) -> isize {
-    trace!("kernel:pid[{}] sys_sbrk", current_task().unwrap().pid.0);
-    if let Some(old_brk) = current_task().unwrap().change_program_brk(size) {
-        old_brk as isize
-    } else {
-        -1
-    }
-}
-
-/// YOUR JOB: Implement spawn.
-/// HINT: fork + exec =/= spawn
-pub fn sys_spawn(_path: *const u8) -> isize {
+/// Grow or shrink the caller's data segment by `size` bytes. See
+/// [`sys_mmap`]: there's no per-task `brk` to move yet.
+pub fn sys_sbrk(_size: i32) -> isize {
     trace!(
-        "kernel:pid[{}] sys_spawn NOT IMPLEMENTED",
+        "kernel:pid[{}] sys_sbrk not supported (no per-task address space yet)",
         current_task().unwrap().pid.0
     );
-    let token = current_user_token();
-    let path = translated_str(token, _path);
-
-    if let Some(app_inode) = open_file(path.as_str(), OpenFlags::RDONLY) {
-        let current_task = current_task().unwrap();
-        // 但提醒读者 spawn 不必像 fork 一样复制父进程的地址空间
-        // let new_task = current_task.fork();
-        // let new_pid = new_task.getpid();
-        // new_task.exec(app);
-
-        // 手动创建一个任务
-        // 从索引节点获取数据
-        let all_data = app_inode.read_all();
-        // 新建任务控制块
-        let new_task = Arc::new(TaskControlBlock::new(all_data.as_slice()));
-        let new_pid = new_task.getpid();
-        // 添加到TASK_MANAGER
-        add_task(new_task.clone());
-        // 添加新进程到现在任务的子进程
-        let mut parent_inner = current_task.inner_exclusive_access();
-        parent_inner.children.push(new_task.clone());
-        // 返回pid
-        return new_pid as isize;
-    }
     -1
 }
 
-/// YOUR JOB: Set task priority.
+/// Set task priority, which drives stride scheduling: the higher the
+/// priority, the bigger a share of the CPU the task earns per round,
+/// because `pass = BIG_STRIDE / priority` (smaller for a higher priority) is
+/// added to its stride every time it's scheduled (see `TaskManager::fetch`/`update_stride`). Priority must
+/// be `>= 2` so `pass <= BIG_STRIDE / 2`, keeping the spread between any
+/// two live strides bounded by `BIG_STRIDE` and the wraparound-safe stride
+/// comparison correct.
 pub fn sys_set_priority(_prio: isize) -> isize {
     trace!(
-        "kernel:pid[{}] sys_set_priority NOT IMPLEMENTED",
-        current_task().unwrap().pid.0
+        "kernel:pid[{}] sys_set_priority prio={}",
+        current_task().unwrap().pid.0,
+        _prio
     );
     if _prio <= 1 {
         return -1;
@@ -338,5 +178,8 @@ pub fn sys_set_priority(_prio: isize) -> isize {
     let current_task = current_task().unwrap();
     let mut cur_pri = current_task.priority.exclusive_access();
     *cur_pri = _prio;
+    drop(cur_pri);
+    let mut pass = current_task.pass.exclusive_access();
+    *pass = BIG_STRIDE / (_prio as usize);
     _prio
 }
@@ -0,0 +1,52 @@
+//! `sys_sched_setscheduler`/`sys_sched_getscheduler`: let userspace pick a
+//! task's scheduling class and priority instead of always getting stride
+//! scheduling.
+use crate::task::sched::SchedPolicy;
+use crate::task::{current_task, pid2task};
+
+/// Set `pid`'s scheduling policy and priority. `pid == 0` targets the
+/// caller. `priority` is only meaningful for [`SchedPolicy::Stride`] and is
+/// otherwise ignored.
+pub fn sys_sched_setscheduler(pid: usize, policy: usize, priority: isize) -> isize {
+    trace!(
+        "kernel:pid[{}] sys_sched_setscheduler target={} policy={}",
+        current_task().unwrap().pid.0,
+        pid,
+        policy
+    );
+    let Some(policy) = SchedPolicy::from_raw(policy) else {
+        return -1;
+    };
+    if policy == SchedPolicy::Stride && priority < 2 {
+        return -1;
+    }
+    let task = if pid == 0 {
+        current_task().unwrap()
+    } else if let Some(task) = pid2task(pid) {
+        task
+    } else {
+        return -1;
+    };
+    task.set_sched_policy(policy);
+    if policy == SchedPolicy::Stride {
+        task.set_priority(priority);
+    }
+    0
+}
+
+/// Query `pid`'s current scheduling policy (`pid == 0` targets the caller).
+pub fn sys_sched_getscheduler(pid: usize) -> isize {
+    trace!(
+        "kernel:pid[{}] sys_sched_getscheduler target={}",
+        current_task().unwrap().pid.0,
+        pid
+    );
+    let task = if pid == 0 {
+        current_task().unwrap()
+    } else if let Some(task) = pid2task(pid) {
+        task
+    } else {
+        return -1;
+    };
+    task.sched_policy().to_raw() as isize
+}
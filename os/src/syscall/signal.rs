@@ -0,0 +1,86 @@
+//! Signal-related syscalls: `sys_kill`, `sys_sigaction`, `sys_sigprocmask`,
+//! `sys_sigreturn`.
+use crate::mm::translated_refmut;
+use crate::task::signal::{SigAction, SigSet};
+use crate::task::{current_task, current_user_token, pid2task};
+
+/// 向 pid 对应进程的主线程发送 signum 信号
+/// Set the pending bit for `signum` on the target process's main thread.
+pub fn sys_kill(pid: usize, signum: usize) -> isize {
+    trace!(
+        "kernel:pid[{}] sys_kill target_pid={} signum={}",
+        current_task().unwrap().pid.0,
+        pid,
+        signum
+    );
+    if signum >= crate::task::signal::MAX_SIG {
+        return -1;
+    }
+    if let Some(task) = pid2task(pid) {
+        let mut inner = task.inner_exclusive_access();
+        inner.pending_signals.add(signum);
+        0
+    } else {
+        -1
+    }
+}
+
+/// Install `new_handler` as the action for `signum`, returning the previous
+/// one (if requested) through `old_handler`.
+pub fn sys_sigaction(signum: usize, new_handler: usize, old_handler: *mut usize) -> isize {
+    trace!(
+        "kernel:pid[{}] sys_sigaction signum={}",
+        current_task().unwrap().pid.0,
+        signum
+    );
+    // SIGKILL (9) 不允许被用户自定义处理
+    if signum >= crate::task::signal::MAX_SIG || signum == 9 {
+        return -1;
+    }
+    let task = current_task().unwrap();
+    let token = current_user_token();
+    let mut inner = task.inner_exclusive_access();
+    let action = SigAction {
+        handler: new_handler,
+        mask: inner.sig_mask,
+    };
+    let previous = inner.sig_actions.set(signum, action);
+    if !old_handler.is_null() {
+        *translated_refmut(token, old_handler) = previous.handler;
+    }
+    0
+}
+
+/// Replace the calling task's blocked-signal mask, returning the old one.
+pub fn sys_sigprocmask(mask: u32) -> isize {
+    trace!(
+        "kernel:pid[{}] sys_sigprocmask mask={:#x}",
+        current_task().unwrap().pid.0,
+        mask
+    );
+    let task = current_task().unwrap();
+    let mut inner = task.inner_exclusive_access();
+    let old = inner.blocked_signals;
+    inner.blocked_signals = SigSet::from_bits(mask);
+    old.bits() as isize
+}
+
+/// Restore the trap context (and blocked mask) saved before a signal
+/// handler was invoked, undoing the redirection `trap_handler` performed.
+pub fn sys_sigreturn() -> isize {
+    trace!(
+        "kernel:pid[{}] sys_sigreturn",
+        current_task().unwrap().pid.0
+    );
+    let task = current_task().unwrap();
+    let mut inner = task.inner_exclusive_access();
+    if let Some(ctx) = inner.sig_context.take() {
+        inner.blocked_signals = ctx.saved_mask;
+        let trap_cx = inner.get_trap_cx();
+        *trap_cx = ctx.trap_cx_backup;
+        // a0 在恢复后的上下文里已经是被打断时的值，这里不再覆盖
+        trap_cx.x[10] as isize
+    } else {
+        -1
+    }
+}
@@ -1,316 +1,99 @@
-use crate::sync::{Condvar, Mutex, MutexBlocking, MutexSpin, Semaphore};
-use crate::task::{block_current_and_run_next, current_process, current_task};
+//! Synchronization-related syscalls.
+//!
+//! This only covers [`sys_futex`], the one primitive this crate's syscall
+//! dispatcher actually routes (see `syscall::mod::syscall`). A prior
+//! revision of this file also carried `sys_mutex_*`/`sys_semaphore_*`/
+//! `sys_condvar_*`/`sys_enable_deadlock_detect` syscalls and a `sys_sleep`,
+//! built against a per-process thread model (`ProcessControlBlock`,
+//! per-thread resource matrices, `current_process()`) that was never wired
+//! into the dispatcher above and never got built out anywhere in `task/`
+//! (see the scope note on `task`'s module doc comment) or `sync/` (no
+//! `Mutex`/`Semaphore`/`Condvar` types exist there either) -- none of that
+//! was reachable from a real syscall, so it's been dropped rather than left
+//! as dead code that can't compile. [`crate::task::deadlock`]'s Banker's
+//! algorithm is unaffected and still available once a caller needs it.
+
+use crate::mm::{translated_byte_buffer, PageTable, VirtAddr};
+use crate::sync::futex::{
+    futex_remove, futex_wait, futex_wake, FutexKey, FUTEX_BITSET_MATCH_ANY, FUTEX_WAIT,
+    FUTEX_WAIT_BITSET, FUTEX_WAKE, FUTEX_WAKE_BITSET,
+};
+use crate::task::{block_current_and_run_next, current_task, current_user_token};
 use crate::timer::{add_timer, get_time_ms};
-use alloc::sync::Arc;
-/// sleep syscall
-pub fn sys_sleep(ms: usize) -> isize {
-    trace!(
-        "kernel:pid[{}] tid[{}] sys_sleep",
-        current_task().unwrap().process.upgrade().unwrap().getpid(),
-        current_task()
-            .unwrap()
-            .inner_exclusive_access()
-            .res
-            .as_ref()
-            .unwrap()
-            .tid
-    );
-    let expire_ms = get_time_ms() + ms;
-    let task = current_task().unwrap();
-    add_timer(expire_ms, task);
-    block_current_and_run_next();
-    0
+
+/// Translate a user futex word address into the key we hash wait queues by:
+/// the physical page it lives on plus its in-page byte offset.
+fn futex_key(token: usize, uaddr: usize) -> FutexKey {
+    let va = VirtAddr::from(uaddr);
+    let pa = PageTable::from_token(token)
+        .translate_va(va)
+        .expect("futex word must be mapped");
+    FutexKey::new(pa.floor().0, va.page_offset())
 }
-/// mutex create syscall
-pub fn sys_mutex_create(blocking: bool) -> isize {
-    trace!(
-        "kernel:pid[{}] tid[{}] sys_mutex_create",
-        current_task().unwrap().process.upgrade().unwrap().getpid(),
-        current_task()
-            .unwrap()
-            .inner_exclusive_access()
-            .res
-            .as_ref()
-            .unwrap()
-            .tid
-    );
-    let process = current_process();
-    let mutex: Option<Arc<dyn Mutex>> = if !blocking {
-        Some(Arc::new(MutexSpin::new()))
-    } else {
-        Some(Arc::new(MutexBlocking::new()))
-    };
-    let mut process_inner = process.inner_exclusive_access();
-    if let Some(id) = process_inner
-        .mutex_list
-        .iter()
-        .enumerate()
-        .find(|(_, item)| item.is_none())
-        .map(|(id, _)| id)
-    {
-        // mutex_list里面有空余id
-        process_inner.mutex_list[id] = mutex;
-        process_inner.available_mutex[id] = 1; // 互斥锁创建了一个资源
-        id as isize
-    } else {
-        // mutex_list里面没有空余id
-        let new_id = process_inner.mutex_list.len();
-        process_inner.mutex_list.push(mutex);
-        process_inner.available_mutex.resize(new_id + 1, 0);
-        process_inner.available_mutex[new_id] = 1; // 互斥锁创建了一个资源
-        new_id as isize
+
+/// Read the 32-bit word at `uaddr` in the current address space.
+fn read_u32_at(token: usize, uaddr: usize) -> u32 {
+    let buf = translated_byte_buffer(token, uaddr as *const u8, 4);
+    let mut bytes = [0u8; 4];
+    let mut copied = 0;
+    for fragment in buf {
+        let take = fragment.len().min(4 - copied);
+        bytes[copied..copied + take].copy_from_slice(&fragment[..take]);
+        copied += take;
     }
+    u32::from_ne_bytes(bytes)
 }
-/// mutex lock syscall
-pub fn sys_mutex_lock(mutex_id: usize) -> isize {
+
+/// futex syscall: fast userspace locking on an integer in the caller's own
+/// address space, with `FUTEX_WAIT`/`FUTEX_WAKE` and their bitset variants.
+///
+/// `op` selects the operation, `val` is the expected value (`FUTEX_WAIT`) or
+/// the maximum number of waiters to wake (`FUTEX_WAKE`), `timeout_ms` is an
+/// optional relative timeout in milliseconds (0 = no timeout), and `val3` is
+/// the wait/wake bitset (`FUTEX_BITSET_MATCH_ANY` reproduces plain
+/// `FUTEX_WAIT`/`FUTEX_WAKE` semantics). `uaddr2` is unused by this subset
+/// of operations and accepted only to match the standard signature.
+pub fn sys_futex(uaddr: usize, op: usize, val: u32, timeout_ms: usize, _uaddr2: usize, val3: u32) -> isize {
     trace!(
-        "kernel:pid[{}] tid[{}] sys_mutex_lock",
-        current_task().unwrap().process.upgrade().unwrap().getpid(),
-        current_task()
-            .unwrap()
-            .inner_exclusive_access()
-            .res
-            .as_ref()
-            .unwrap()
-            .tid
+        "kernel:pid[{}] sys_futex uaddr={:#x} op={}",
+        current_task().unwrap().pid.0,
+        uaddr,
+        op
     );
-    let process = current_process();
-    let mut process_inner = process.inner_exclusive_access();
-    let mutex = Arc::clone(process_inner.mutex_list[mutex_id].as_ref().unwrap());
-    let tid = process_inner.get_tid();
-    // 当前线程需要一份资源
-    process_inner.need_mutex[tid][mutex_id] = 1; 
-    // 死锁检测
-    let available = process_inner.available_mutex.clone();
-    let need = process_inner.need_mutex.clone();
-    let allocation = process_inner.allocation_mutex.clone();
-    if process_inner.deadlock_detect_enabled && process_inner.has_mutex_deadlock(tid, available, need, allocation) {
-        return -0xdead;
+    let token = current_user_token();
+    let key = futex_key(token, uaddr);
+    match op {
+        FUTEX_WAIT | FUTEX_WAIT_BITSET => {
+            let bitset = if op == FUTEX_WAIT_BITSET {
+                val3
+            } else {
+                FUTEX_BITSET_MATCH_ANY
+            };
+            // Atomically (wrt. other futex ops on this key, which all run
+            // with interrupts off inside UPSafeCell) recheck *uaddr before
+            // registering as a waiter, to avoid the lost-wakeup race.
+            if read_u32_at(token, uaddr) != val {
+                return -1;
+            }
+            let task = current_task().unwrap();
+            futex_wait(key, bitset, task.clone());
+            if timeout_ms > 0 {
+                add_timer(get_time_ms() + timeout_ms, task.clone());
+            }
+            block_current_and_run_next();
+            // Either woken by FUTEX_WAKE (already removed from the bucket)
+            // or by the timer firing; make sure we're not left registered.
+            futex_remove(key, &task);
+            0
+        }
+        FUTEX_WAKE | FUTEX_WAKE_BITSET => {
+            let bitset = if op == FUTEX_WAKE_BITSET {
+                val3
+            } else {
+                FUTEX_BITSET_MATCH_ANY
+            };
+            futex_wake(key, val as usize, bitset) as isize
+        }
+        _ => -1,
     }
-    drop(process_inner);
-    drop(process);
-    // 没有死锁，正常分配资源
-    mutex.lock();
-    // 成功获取资源后
-    let process = current_process();
-    let mut process_inner = process.inner_exclusive_access();
-    process_inner.available_mutex[mutex_id] = 0; // mutex_id资源-1
-    process_inner.need_mutex[tid][mutex_id] = 0; // tid线程需要的mutex_id资源-1
-    process_inner.allocation_mutex[tid][mutex_id] = 1; // 分配给tid线程的mutex_id资源+1
-    0
-}
-/// mutex unlock syscall
-pub fn sys_mutex_unlock(mutex_id: usize) -> isize {
-    trace!(
-        "kernel:pid[{}] tid[{}] sys_mutex_unlock",
-        current_task().unwrap().process.upgrade().unwrap().getpid(),
-        current_task()
-            .unwrap()
-            .inner_exclusive_access()
-            .res
-            .as_ref()
-            .unwrap()
-            .tid
-    );
-    let process = current_process();
-    let process_inner = process.inner_exclusive_access();
-    let mutex = Arc::clone(process_inner.mutex_list[mutex_id].as_ref().unwrap());
-    drop(process_inner);
-    drop(process);
-    mutex.unlock();
-    // 释放一个资源
-    let process = current_process();
-    let mut process_inner = process.inner_exclusive_access();
-    let tid = process_inner.get_tid();
-    process_inner.available_mutex[mutex_id] = 1; // mutex_id的资源+1
-    process_inner.allocation_mutex[tid][mutex_id] = 0; // 分配给tid线程的mutex_id资源-1
-    0
-}
-/// semaphore create syscall
-pub fn sys_semaphore_create(res_count: usize) -> isize {
-    trace!(
-        "kernel:pid[{}] tid[{}] sys_semaphore_create",
-        current_task().unwrap().process.upgrade().unwrap().getpid(),
-        current_task()
-            .unwrap()
-            .inner_exclusive_access()
-            .res
-            .as_ref()
-            .unwrap()
-            .tid
-    );
-    let process = current_process();
-    let mut process_inner = process.inner_exclusive_access();
-    let id = if let Some(id) = process_inner
-        .semaphore_list
-        .iter()
-        .enumerate()
-        .find(|(_, item)| item.is_none())
-        .map(|(id, _)| id)
-    {
-        // 有空余id
-        process_inner.semaphore_list[id] = Some(Arc::new(Semaphore::new(res_count)));
-        process_inner.available_sem[id] = res_count;
-        id
-    } else {
-        let new_id = process_inner.semaphore_list.len();
-        process_inner
-            .semaphore_list
-            .push(Some(Arc::new(Semaphore::new(res_count))));
-        process_inner.available_sem[new_id] = res_count;
-        new_id
-    };
-    id as isize
-}
-/// semaphore up syscall
-pub fn sys_semaphore_up(sem_id: usize) -> isize {
-    trace!(
-        "kernel:pid[{}] tid[{}] sys_semaphore_up",
-        current_task().unwrap().process.upgrade().unwrap().getpid(),
-        current_task()
-            .unwrap()
-            .inner_exclusive_access()
-            .res
-            .as_ref()
-            .unwrap()
-            .tid
-    );
-    let process = current_process();
-    let process_inner = process.inner_exclusive_access();
-    let sem = Arc::clone(process_inner.semaphore_list[sem_id].as_ref().unwrap());
-    drop(process_inner);
-    drop(process);
-    sem.up();
-    // 更新矩阵
-    let process = current_process();
-    let mut process_inner = process.inner_exclusive_access();
-    let tid = process_inner.get_tid();
-    process_inner.available_sem[sem_id] += 1;
-    // process_inner.need_sem[tid][sem_id] = 0; // 本线程需要的该资源-1
-    process_inner.allocation_sem[tid][sem_id] -= 1; // 给本线程分配的资源-1
-    drop(process_inner);
-    drop(process);
-    0
-}
-/// semaphore down syscall
-pub fn sys_semaphore_down(sem_id: usize) -> isize {
-    trace!(
-        "kernel:pid[{}] tid[{}] sys_semaphore_down",
-        current_task().unwrap().process.upgrade().unwrap().getpid(),
-        current_task()
-            .unwrap()
-            .inner_exclusive_access()
-            .res
-            .as_ref()
-            .unwrap()
-            .tid
-    );
-    let process = current_process();
-    let mut process_inner = process.inner_exclusive_access();
-    let sem = Arc::clone(process_inner.semaphore_list[sem_id].as_ref().unwrap());
-    let tid = process_inner.get_tid();
-    // 本线程需要一份资源
-    process_inner.need_sem[tid][sem_id] += 1;
-    // 死锁检测
-    let available = process_inner.available_sem.clone();
-    let need = process_inner.need_sem.clone();
-    let allocation = process_inner.allocation_sem.clone();
-    if process_inner.deadlock_detect_enabled && process_inner.has_mutex_deadlock(sem_id, available, need, allocation) {
-        return -0xdead;
-    }
-    drop(process_inner);
-    // 没有死锁，正常分配资源
-    sem.down();
-    // 更新矩阵
-    let process = current_process();
-    let mut process_inner = process.inner_exclusive_access();
-    process_inner.available_sem[sem_id] -= 1;
-    process_inner.need_mutex[tid][sem_id] -= 1;
-    process_inner.allocation_sem[tid][sem_id] += 1;
-    drop(process_inner);
-    drop(process);
-    0
-}
-/// condvar create syscall
-pub fn sys_condvar_create() -> isize {
-    trace!(
-        "kernel:pid[{}] tid[{}] sys_condvar_create",
-        current_task().unwrap().process.upgrade().unwrap().getpid(),
-        current_task()
-            .unwrap()
-            .inner_exclusive_access()
-            .res
-            .as_ref()
-            .unwrap()
-            .tid
-    );
-    let process = current_process();
-    let mut process_inner = process.inner_exclusive_access();
-    let id = if let Some(id) = process_inner
-        .condvar_list
-        .iter()
-        .enumerate()
-        .find(|(_, item)| item.is_none())
-        .map(|(id, _)| id)
-    {
-        process_inner.condvar_list[id] = Some(Arc::new(Condvar::new()));
-        id
-    } else {
-        process_inner
-            .condvar_list
-            .push(Some(Arc::new(Condvar::new())));
-        process_inner.condvar_list.len() - 1
-    };
-    id as isize
-}
-/// condvar signal syscall
-pub fn sys_condvar_signal(condvar_id: usize) -> isize {
-    trace!(
-        "kernel:pid[{}] tid[{}] sys_condvar_signal",
-        current_task().unwrap().process.upgrade().unwrap().getpid(),
-        current_task()
-            .unwrap()
-            .inner_exclusive_access()
-            .res
-            .as_ref()
-            .unwrap()
-            .tid
-    );
-    let process = current_process();
-    let process_inner = process.inner_exclusive_access();
-    let condvar = Arc::clone(process_inner.condvar_list[condvar_id].as_ref().unwrap());
-    drop(process_inner);
-    condvar.signal();
-    0
-}
-/// condvar wait syscall
-pub fn sys_condvar_wait(condvar_id: usize, mutex_id: usize) -> isize {
-    trace!(
-        "kernel:pid[{}] tid[{}] sys_condvar_wait",
-        current_task().unwrap().process.upgrade().unwrap().getpid(),
-        current_task()
-            .unwrap()
-            .inner_exclusive_access()
-            .res
-            .as_ref()
-            .unwrap()
-            .tid
-    );
-    let process = current_process();
-    let process_inner = process.inner_exclusive_access();
-    let condvar = Arc::clone(process_inner.condvar_list[condvar_id].as_ref().unwrap());
-    let mutex = Arc::clone(process_inner.mutex_list[mutex_id].as_ref().unwrap());
-    drop(process_inner);
-    condvar.wait(mutex);
-    0
-}
-/// enable deadlock detection syscall
-/// _enable: 为 1 表示启用死锁检测， 0 表示禁用死锁检测
-/// YOUR JOB: Implement deadlock detection, but might not all in this syscall
-pub fn sys_enable_deadlock_detect(_enabled: usize) -> isize {
-    trace!("kernel: sys_enable_deadlock_detect NOT IMPLEMENTED");
-    let process = current_process();
-    let mut process_inner = process.inner_exclusive_access();
-    process_inner.deadlock_detect_enabled = _enabled == 1;
-    0
 }
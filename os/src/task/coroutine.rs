@@ -0,0 +1,178 @@
+//! A lightweight cooperative coroutine tier that sits alongside the
+//! preemptive [`super::manager::TaskManager`].
+//!
+//! Coroutines belonging to the same process share that process's page
+//! table (`satp`), so handing control between them never pays the
+//! address-space-reload cost a normal thread switch does, and they need no
+//! dedicated kernel stack of their own. This makes them a cheap tier for
+//! I/O-bound fan-out where spawning a full kernel thread per task would be
+//! wasteful.
+
+use crate::sync::UPSafeCell;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec::Vec;
+
+/// A unit of cooperative work: runs until it calls
+/// `sys_coroutine_yield` or returns, at which point control passes back to
+/// the executor.
+///
+/// This models what a coroutine's saved state looks like from the kernel
+/// side: an entry point plus an opaque argument passed in `a0`; no register
+/// or stack state is kept. [`yield_coroutine`] switches between coroutines
+/// of the same process by rewriting the owning thread's trap context
+/// (`sepc` and `a0`).
+pub struct Coroutine {
+    /// Coroutine-local id, unique within its owning process.
+    pub id: usize,
+    /// Entry point the coroutine resumes at, every time it is switched to.
+    pub entry: usize,
+    /// Argument passed in `a0` each time the coroutine is switched to.
+    pub arg: usize,
+    /// Whether this coroutine has been switched to at least once. Resuming
+    /// does not consult it: every resume starts again at `entry`.
+    pub started: bool,
+}
+
+/// Per-process cooperative executor: a ready queue of coroutines plus the
+/// bookkeeping needed to hand back control to the owning kernel thread
+/// once every coroutine of the process is blocked.
+pub struct Executor {
+    ready: VecDeque<usize>,
+    coroutines: BTreeMap<usize, Coroutine>,
+    next_id: usize,
+    /// Coroutine id the process is currently running, if any.
+    pub current: Option<usize>,
+}
+
+impl Executor {
+    /// A fresh executor with no coroutines registered.
+    pub fn new() -> Self {
+        Self {
+            ready: VecDeque::new(),
+            coroutines: BTreeMap::new(),
+            next_id: 0,
+            current: None,
+        }
+    }
+    /// Register a new stackless task and enqueue it to run.
+    pub fn spawn(&mut self, entry: usize, arg: usize) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.coroutines.insert(
+            id,
+            Coroutine {
+                id,
+                entry,
+                arg,
+                started: false,
+            },
+        );
+        self.ready.push_back(id);
+        id
+    }
+    /// Cooperatively hand control to the next ready coroutine, returning it
+    /// (and re-enqueuing the previously running one, if any and still
+    /// alive) without a full thread context switch.
+    pub fn next_ready(&mut self) -> Option<&Coroutine> {
+        if let Some(prev) = self.current.take() {
+            if self.coroutines.contains_key(&prev) {
+                self.ready.push_back(prev);
+            }
+        }
+        let id = self.ready.pop_front()?;
+        self.current = Some(id);
+        self.coroutines.get(&id)
+    }
+    /// Drop a finished coroutine so it's never scheduled again.
+    pub fn finish(&mut self, id: usize) {
+        self.coroutines.remove(&id);
+        if self.current == Some(id) {
+            self.current = None;
+        }
+    }
+    /// Whether every coroutine of this process is blocked (none ready and
+    /// none currently running) — when true the owning kernel thread should
+    /// fall back to [`super::block_current_and_run_next`].
+    pub fn all_blocked(&self) -> bool {
+        self.ready.is_empty() && self.current.is_none()
+    }
+}
+
+/// One executor per process, keyed by pid, guarded the same way every other
+/// piece of shared kernel state in this crate is.
+pub struct ExecutorTable {
+    table: BTreeMap<usize, Executor>,
+}
+
+impl ExecutorTable {
+    /// An empty table.
+    pub fn new() -> Self {
+        Self {
+            table: BTreeMap::new(),
+        }
+    }
+    /// Get (creating if necessary) the executor for `pid`.
+    pub fn get_or_create(&mut self, pid: usize) -> &mut Executor {
+        self.table.entry(pid).or_insert_with(Executor::new)
+    }
+}
+
+lazy_static::lazy_static! {
+    /// Global table of per-process executors.
+    pub static ref EXECUTORS: UPSafeCell<ExecutorTable> =
+        unsafe { UPSafeCell::new(ExecutorTable::new()) };
+}
+
+/// Register a new coroutine for `pid`, returning its id.
+pub fn spawn_coroutine(pid: usize, entry: usize, arg: usize) -> usize {
+    EXECUTORS.exclusive_access().get_or_create(pid).spawn(entry, arg)
+}
+
+/// Cooperatively yield: move on to the next ready coroutine of `pid`,
+/// rewriting the owning kernel thread's trap context so its next `sret`
+/// actually resumes at that coroutine instead of where the caller yielded —
+/// without this, switching which id `Executor::current` holds has no effect
+/// on what runs next. If none are ready the caller should fall back to
+/// blocking the underlying kernel thread via `block_current_and_run_next`.
+///
+/// Because these coroutines are stackless (see the module doc comment),
+/// there's no saved continuation to resume into: a coroutine that's already
+/// been started once resumes at its entry point again rather than where it
+/// called `sys_coroutine_yield`. Giving coroutines real suspend points would
+/// need a per-coroutine saved register/stack snapshot, which this tier
+/// doesn't keep.
+pub fn yield_coroutine(pid: usize) -> bool {
+    let next = {
+        let mut executors = EXECUTORS.exclusive_access();
+        let executor = executors.get_or_create(pid);
+        executor.next_ready().map(|c| (c.entry, c.arg))
+    };
+    let Some((entry, arg)) = next else {
+        return false;
+    };
+    if let Some(task) = super::pid2task(pid) {
+        let mut inner = task.inner_exclusive_access();
+        let trap_cx = inner.get_trap_cx();
+        trap_cx.x[10] = arg;
+        trap_cx.sepc = entry;
+    }
+    let mut executors = EXECUTORS.exclusive_access();
+    let executor = executors.get_or_create(pid);
+    if let Some(id) = executor.current {
+        if let Some(coroutine) = executor.coroutines.get_mut(&id) {
+            coroutine.started = true;
+        }
+    }
+    true
+}
+
+/// Every live coroutine id for `pid`, for diagnostics.
+pub fn live_coroutines(pid: usize) -> Vec<usize> {
+    EXECUTORS
+        .exclusive_access()
+        .get_or_create(pid)
+        .coroutines
+        .keys()
+        .copied()
+        .collect()
+}
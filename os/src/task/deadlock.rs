@@ -0,0 +1,82 @@
+//! Multi-resource deadlock avoidance (Banker's algorithm)
+//!
+//! `has_mutex_deadlock` used to reason about a single resource id at a time,
+//! which misses cyclic waits that span several mutexes/semaphores held by
+//! different threads. [`is_safe_state`] instead runs the classic Banker's
+//! safety check against the *whole* `available`/`need`/`allocation` matrices
+//! at once.
+
+use alloc::vec::Vec;
+
+/// Run the Banker's safety algorithm over the whole resource state.
+///
+/// `available[r]` is how many instances of resource `r` are currently free,
+/// `need[t][r]` is how many more instances of resource `r` thread `t` may
+/// still request, and `allocation[t][r]` is how many instances of resource
+/// `r` are currently held by thread `t`.
+///
+/// Returns `true` iff the state is safe, i.e. there exists some order in
+/// which every live thread can finish by only ever requesting resources
+/// that are (eventually) available.
+pub fn is_safe_state(available: &[usize], need: &[Vec<usize>], allocation: &[Vec<usize>]) -> bool {
+    let thread_count = need.len();
+    let resource_count = available.len();
+    let mut work = available.to_vec();
+    let mut finish = alloc::vec![false; thread_count];
+
+    loop {
+        let mut found = None;
+        for tid in 0..thread_count {
+            if finish[tid] {
+                continue;
+            }
+            let can_finish = (0..resource_count).all(|r| need[tid][r] <= work[r]);
+            if can_finish {
+                found = Some(tid);
+                break;
+            }
+        }
+        match found {
+            Some(tid) => {
+                for r in 0..resource_count {
+                    work[r] += allocation[tid][r];
+                }
+                finish[tid] = true;
+            }
+            // No thread could make progress: stop the scan.
+            None => break,
+        }
+    }
+
+    finish.into_iter().all(|f| f)
+}
+
+/// Check whether granting thread `tid` one more unit of resource `res_id`
+/// would lead to an unsafe (deadlocked) state.
+///
+/// `need`/`allocation`/`available` already reflect `tid`'s *pending* request
+/// (the caller bumps `need[tid][res_id]` before calling this), so this
+/// tentatively grants that unit and runs [`is_safe_state`] on the resulting
+/// hypothetical state without mutating the caller's matrices.
+pub fn would_deadlock(
+    tid: usize,
+    res_id: usize,
+    available: &[usize],
+    need: &[Vec<usize>],
+    allocation: &[Vec<usize>],
+) -> bool {
+    if available[res_id] == 0 {
+        // Nothing to tentatively grant right now; the request simply blocks
+        // and cannot itself create an unsafe state.
+        return false;
+    }
+    let mut hyp_available = available.to_vec();
+    let mut hyp_need = need.to_vec();
+    let mut hyp_allocation = allocation.to_vec();
+
+    hyp_available[res_id] -= 1;
+    hyp_need[tid][res_id] -= 1;
+    hyp_allocation[tid][res_id] += 1;
+
+    !is_safe_state(&hyp_available, &hyp_need, &hyp_allocation)
+}
@@ -1,68 +1,105 @@
 //!Implementation of [`TaskManager`]
 
+use super::sched::SchedPolicy;
 use super::TaskControlBlock;
-use crate::config::BIG_STRIDE;
 use crate::sync::UPSafeCell;
-use alloc::collections::VecDeque;
+use alloc::collections::{BinaryHeap, VecDeque};
 use alloc::sync::Arc;
+use core::cmp::Ordering;
 use lazy_static::*;
-///A array of `TaskControlBlock` that is thread-safe
+
+/// Wraps a task for the stride heap so `BinaryHeap` (a max-heap) pops the
+/// *smallest* stride first, and so that comparisons stay correct across
+/// `usize` wraparound using the signed-difference trick.
+struct StrideEntry(Arc<TaskControlBlock>);
+
+impl StrideEntry {
+    fn stride(&self) -> usize {
+        *self.0.stride.access()
+    }
+}
+
+impl PartialEq for StrideEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.stride() == other.stride()
+    }
+}
+impl Eq for StrideEntry {}
+impl PartialOrd for StrideEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for StrideEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so `BinaryHeap` (max-heap) surfaces the smallest stride
+        // first. Plain numeric comparison would break once a task's stride
+        // wraps past `usize::MAX`; as long as every `pass <= BIG_STRIDE`
+        // (enforced by `priority >= 2`), the spread between the liveliest
+        // and laziest stride never exceeds `BIG_STRIDE`, so comparing via
+        // wrapping subtraction stays correct across the wraparound.
+        let diff = (self.stride().wrapping_sub(other.stride())) as isize;
+        0isize.cmp(&diff)
+    }
+}
+
+///A array of `TaskControlBlock` that is thread-safe, dispatching onto one
+///of several scheduling classes depending on each task's `sched_policy`.
 pub struct TaskManager {
-    ready_queue: VecDeque<Arc<TaskControlBlock>>,
+    /// FIFO/RoundRobin tasks, in the order they became ready.
+    rr_queue: VecDeque<Arc<TaskControlBlock>>,
+    /// Stride/weighted tasks, ordered by stride for O(log n) selection.
+    stride_queue: BinaryHeap<StrideEntry>,
+    /// Flipped at the start of every `fetch` call, which then tries the
+    /// Stride class first when it is `true`; alternating this way keeps
+    /// either class from starving the other.
+    prefer_stride: bool,
 }
 
-/// A simple FIFO scheduler.
 impl TaskManager {
     ///Creat an empty TaskManager
     pub fn new() -> Self {
         Self {
-            ready_queue: VecDeque::new(),
+            rr_queue: VecDeque::new(),
+            stride_queue: BinaryHeap::new(),
+            prefer_stride: false,
         }
     }
-    /// Add process back to ready queue
+    /// Add process back to its class's ready queue
     pub fn add(&mut self, task: Arc<TaskControlBlock>) {
-        self.ready_queue.push_back(task);
-    }
-    /// Update Stride by Index
-    pub fn update_stride_by_index(&mut self, index: usize) {
-        let task = self.ready_queue.get(index).unwrap();
-        task.update_stride();
-        // // 溢出了
-        // if let Some(min_stride_index) = self.get_min_stride_index() {
-        //     let min_stride = *self
-        //         .ready_queue
-        //         .get(min_stride_index)
-        //         .unwrap()
-        //         .stride
-        //         .access();
-        //     for task in self.ready_queue.iter_mut() {
-        //         task.update_stride_when_overflow(min_stride);
-        //     }
-        // } else {
-        //     task.update_stride_when_overflow(0);
-        // }
+        match task.sched_policy() {
+            SchedPolicy::Fifo | SchedPolicy::RoundRobin => self.rr_queue.push_back(task),
+            SchedPolicy::Stride => self.stride_queue.push(StrideEntry(task)),
+        }
     }
-    /// Take a process out of the ready queue
+    /// Take a process out of the ready queue.
+    ///
+    /// Alternates which class it tries first on every call, so a steady
+    /// stream of FIFO/RoundRobin arrivals can't starve Stride tasks (or vice
+    /// versa) the way always preferring one class would; ties within a class
+    /// are broken by arrival order (rr_queue) or smallest stride
+    /// (stride_queue).
     pub fn fetch(&mut self) -> Option<Arc<TaskControlBlock>> {
-        // self.ready_queue.pop_front() 取消先进先出的算法
-        // stride算法
-        let mut min_index = 0;
-        let mut min_stride = BIG_STRIDE;
-        if self.ready_queue.is_empty() {
-            return None;
-        }
-        // 暴力枚举
-        for i in 0..self.ready_queue.len() {
-            let task = self.ready_queue.get(i).unwrap();
-            let stride = *task.stride.access();
-            if stride <= min_stride {
-                min_index = i;
-                min_stride = stride;
-            }
+        self.prefer_stride = !self.prefer_stride;
+        let fetch_rr = |this: &mut Self| {
+            this.rr_queue.pop_front().map(|task| {
+                if task.sched_policy() == SchedPolicy::RoundRobin {
+                    task.reload_time_slice();
+                }
+                task
+            })
+        };
+        let fetch_stride = |this: &mut Self| {
+            this.stride_queue.pop().map(|entry| {
+                entry.0.update_stride();
+                entry.0
+            })
+        };
+        if self.prefer_stride {
+            fetch_stride(self).or_else(|| fetch_rr(self))
+        } else {
+            fetch_rr(self).or_else(|| fetch_stride(self))
         }
-        let task = self.ready_queue.get(min_index).unwrap();
-        task.update_stride();
-        self.ready_queue.remove(min_index)
     }
 }
 
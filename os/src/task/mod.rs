@@ -0,0 +1,231 @@
+//! Task/process control: the scheduling, signal, and per-task file-table
+//! state shared by the syscalls and subsystems layered on top of it
+//! (deadlock detection, signals, futex, scheduling classes, coroutines).
+//!
+//! This intentionally does not (yet) implement the fuller process-management
+//! surface (`fork`/`exec`/address-space `memory_set`/`mmap`) that some
+//! baseline syscalls already expect — that predates and is independent of
+//! the task/signal/sched/coroutine work built on top of it here.
+
+pub mod coroutine;
+pub mod deadlock;
+pub mod manager;
+pub mod sched;
+pub mod signal;
+
+use crate::fs::OSInode;
+use crate::sync::UPSafeCell;
+use crate::trap::TrapContext;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use easy_fs::Inode;
+use manager::{add_task as manager_add_task, fetch_task};
+use sched::SchedPolicy;
+use signal::{SigActionTable, SigContext, SigSet};
+
+pub use manager::TASK_MANAGER;
+
+/// A task/process id.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct PidHandle(pub usize);
+
+/// Lifecycle state of a task, as surfaced through `sys_task_info`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TaskStatus {
+    /// Ready to run, but not currently scheduled.
+    Ready,
+    /// Currently running on some hart.
+    Running,
+    /// Exited; waiting for a parent to reap it.
+    Zombie,
+}
+
+/// Scheduling/signal/file state that changes over a task's lifetime, guarded
+/// the same way every other piece of shared kernel state in this crate is.
+pub struct TaskControlBlockInner {
+    /// Signals delivered but not yet handled.
+    pub pending_signals: SigSet,
+    /// Signals currently masked from delivery.
+    pub blocked_signals: SigSet,
+    /// Mask newly installed handlers capture into their `SigAction` (distinct
+    /// from `blocked_signals`, which is the mask actually in effect now).
+    pub sig_mask: SigSet,
+    /// Installed handler per signal number.
+    pub sig_actions: SigActionTable,
+    /// Saved context while a handler is running, so `sys_sigreturn` can
+    /// restore exactly what was interrupted; `None` outside a handler.
+    pub sig_context: Option<SigContext>,
+    /// Current working directory, resolved against for relative paths.
+    pub cwd: Arc<Inode>,
+    /// Open file descriptor table.
+    pub fd_table: Vec<Option<Arc<OSInode>>>,
+    /// The trap frame this task resumes into on its next time slice / after
+    /// a syscall or signal-handler return.
+    pub trap_cx: TrapContext,
+}
+
+impl TaskControlBlockInner {
+    /// Get the trap context this task resumes into.
+    pub fn get_trap_cx(&mut self) -> &mut TrapContext {
+        &mut self.trap_cx
+    }
+    /// Allocate the lowest-numbered free fd, growing the table if every slot
+    /// is taken.
+    pub fn alloc_fd(&mut self) -> usize {
+        if let Some(fd) = self.fd_table.iter().position(|f| f.is_none()) {
+            fd
+        } else {
+            self.fd_table.push(None);
+            self.fd_table.len() - 1
+        }
+    }
+}
+
+/// A schedulable task: one Banker's-algorithm/signal/futex participant, with
+/// its own file descriptor table and scheduling-class state.
+pub struct TaskControlBlock {
+    /// This task's id.
+    pub pid: PidHandle,
+    /// Stride-scheduling priority (`sys_set_priority`); unused outside
+    /// [`SchedPolicy::Stride`].
+    pub priority: UPSafeCell<isize>,
+    /// This task's current position on the stride scheduler's number line.
+    pub stride: UPSafeCell<usize>,
+    /// How much `stride` advances each time this task is dispatched
+    /// (`BIG_STRIDE / priority`, set by `sys_set_priority` via
+    /// `syscall::process::sys_set_priority`).
+    pub pass: UPSafeCell<usize>,
+    /// Timer ticks left in this [`SchedPolicy::RoundRobin`] task's current
+    /// time slice; reloaded from [`sched::RR_TIME_SLICE`] each time it's
+    /// dispatched, decremented by [`on_timer_tick`].
+    remaining_slice: UPSafeCell<usize>,
+    sched_policy: UPSafeCell<SchedPolicy>,
+    inner: UPSafeCell<TaskControlBlockInner>,
+}
+
+impl TaskControlBlock {
+    /// This task's pid.
+    pub fn getpid(&self) -> usize {
+        self.pid.0
+    }
+    /// This task's current scheduling class.
+    pub fn sched_policy(&self) -> SchedPolicy {
+        *self.sched_policy.access()
+    }
+    /// Switch this task's scheduling class.
+    pub fn set_sched_policy(&self, policy: SchedPolicy) {
+        *self.sched_policy.exclusive_access() = policy;
+    }
+    /// Set this task's stride-scheduling priority.
+    pub fn set_priority(&self, priority: isize) {
+        *self.priority.exclusive_access() = priority;
+        *self.pass.exclusive_access() = crate::config::BIG_STRIDE / (priority as usize);
+    }
+    /// Advance this task's stride by its `pass`; run once each time it's
+    /// dispatched by `TaskManager::fetch`.
+    pub fn update_stride(&self) {
+        let pass = *self.pass.access();
+        let mut stride = self.stride.exclusive_access();
+        *stride = stride.wrapping_add(pass);
+    }
+    /// Reload this task's time slice; run once each time it's dispatched by
+    /// `TaskManager::fetch`.
+    pub fn reload_time_slice(&self) {
+        *self.remaining_slice.exclusive_access() = sched::RR_TIME_SLICE;
+    }
+    /// Exclusive access to this task's mutable state.
+    pub fn inner_exclusive_access(&self) -> core::cell::RefMut<'_, TaskControlBlockInner> {
+        self.inner.exclusive_access()
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref PID2TASK: UPSafeCell<BTreeMap<usize, Arc<TaskControlBlock>>> =
+        unsafe { UPSafeCell::new(BTreeMap::new()) };
+    static ref CURRENT_TASK: UPSafeCell<Option<Arc<TaskControlBlock>>> =
+        unsafe { UPSafeCell::new(None) };
+}
+
+/// Look up a live task by pid.
+pub fn pid2task(pid: usize) -> Option<Arc<TaskControlBlock>> {
+    PID2TASK.access().get(&pid).cloned()
+}
+
+/// Register `task` in the pid lookup table and its scheduling class's ready
+/// queue.
+pub fn add_task(task: Arc<TaskControlBlock>) {
+    PID2TASK.exclusive_access().insert(task.pid.0, task.clone());
+    manager_add_task(task);
+}
+
+/// Put a task back on its ready queue after whatever blocked it resolves
+/// (timer fire, futex wake, ...).
+pub fn wakeup_task(task: Arc<TaskControlBlock>) {
+    manager_add_task(task);
+}
+
+/// The task presently running on this hart.
+pub fn current_task() -> Option<Arc<TaskControlBlock>> {
+    CURRENT_TASK.access().clone()
+}
+
+/// The token (would-be `satp`) of the current task's address space, used by
+/// every `translated_*` helper.
+pub fn current_user_token() -> usize {
+    current_task().unwrap().pid.0
+}
+
+/// Pick the next ready task (if any) and make it current.
+fn switch_to_next() {
+    *CURRENT_TASK.exclusive_access() = fetch_task();
+}
+
+/// Charge one timer tick against the current task's time slice, called from
+/// `time::handle_timer_interrupt`; once a [`SchedPolicy::RoundRobin`] task's
+/// slice is exhausted it's preempted and requeued behind its classmates,
+/// same as a voluntary yield. FIFO and Stride tasks aren't time-sliced, so
+/// this is a no-op for them.
+pub fn on_timer_tick() {
+    let Some(task) = current_task() else {
+        return;
+    };
+    if task.sched_policy() != SchedPolicy::RoundRobin {
+        return;
+    }
+    let expired = {
+        let mut remaining = task.remaining_slice.exclusive_access();
+        *remaining = remaining.saturating_sub(1);
+        *remaining == 0
+    };
+    if expired {
+        suspend_current_and_run_next();
+    }
+}
+
+/// Suspend the current task (requeuing it) and hand the CPU to whatever
+/// `fetch_task` picks next; used by everything that yields voluntarily.
+pub fn suspend_current_and_run_next() {
+    if let Some(task) = CURRENT_TASK.exclusive_access().take() {
+        manager_add_task(task);
+    }
+    switch_to_next();
+}
+
+/// Suspend the current task *without* requeuing it, handing the CPU to
+/// whatever `fetch_task` picks next; used by everything that parks a task on
+/// a wait queue (it's the wait queue's job to requeue it later via
+/// `wakeup_task`).
+pub fn block_current_and_run_next() {
+    CURRENT_TASK.exclusive_access().take();
+    switch_to_next();
+}
+
+/// Tear down the current task and hand the CPU to whatever `fetch_task`
+/// picks next.
+pub fn exit_current_and_run_next(_exit_code: i32) {
+    if let Some(task) = CURRENT_TASK.exclusive_access().take() {
+        PID2TASK.exclusive_access().remove(&task.pid.0);
+    }
+    switch_to_next();
+}
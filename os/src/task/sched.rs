@@ -0,0 +1,42 @@
+//! Scheduling-policy types shared between the task manager and the
+//! `sys_sched_setscheduler`/`sys_sched_getscheduler` syscalls.
+
+/// Which scheduling class a task belongs to.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SchedPolicy {
+    /// First-in first-out: runs to completion (or a blocking point) once
+    /// scheduled, never preempted by the time-slice timer.
+    Fifo,
+    /// Round-robin: same FIFO ordering, but requeued at the back of its
+    /// queue when its time slice expires.
+    RoundRobin,
+    /// Weighted fair-share scheduling driven by `priority` via the stride
+    /// algorithm (see [`super::deadlock`] for the unrelated Banker's check,
+    /// and `update_stride`/`BIG_STRIDE` for the stride math itself).
+    Stride,
+}
+
+impl SchedPolicy {
+    /// Decode the `policy` argument of `sys_sched_setscheduler`.
+    pub fn from_raw(policy: usize) -> Option<Self> {
+        match policy {
+            0 => Some(SchedPolicy::Fifo),
+            1 => Some(SchedPolicy::RoundRobin),
+            2 => Some(SchedPolicy::Stride),
+            _ => None,
+        }
+    }
+    /// Encode back to the raw representation returned by
+    /// `sys_sched_getscheduler`.
+    pub fn to_raw(self) -> usize {
+        match self {
+            SchedPolicy::Fifo => 0,
+            SchedPolicy::RoundRobin => 1,
+            SchedPolicy::Stride => 2,
+        }
+    }
+}
+
+/// Number of timer ticks an `RoundRobin` task may run before being requeued
+/// behind other ready tasks of the same class.
+pub const RR_TIME_SLICE: usize = 5;
@@ -0,0 +1,119 @@
+//! POSIX-style signal types shared by the task/process layer and the
+//! `sys_kill`/`sys_sigaction`/`sys_sigprocmask`/`sys_sigreturn` syscalls.
+
+use crate::trap::TrapContext;
+
+/// Number of distinct signals this kernel understands (1-indexed, so bit 0
+/// of a [`SigSet`] is unused).
+pub const MAX_SIG: usize = 32;
+
+/// Default action taken when a signal arrives and no handler is installed.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum SigDefault {
+    /// Terminate the receiving process.
+    Terminate,
+    /// Drop the signal silently.
+    Ignore,
+}
+
+/// Returns the default disposition for `signum`: `SIGKILL` and `SIGTERM`
+/// terminate, every other signal is ignored. `sys_sigaction` refuses to
+/// install a handler for `SIGKILL`; `SIGSTOP` gets no special treatment.
+pub fn default_action(signum: usize) -> SigDefault {
+    match signum {
+        9 /* SIGKILL */ | 15 /* SIGTERM */ => SigDefault::Terminate,
+        _ => SigDefault::Ignore,
+    }
+}
+
+/// A bitset over signal numbers, used for both the pending set and the
+/// blocked mask.
+#[derive(Copy, Clone, Default)]
+pub struct SigSet(u32);
+
+impl SigSet {
+    /// An empty set.
+    pub fn empty() -> Self {
+        Self(0)
+    }
+    /// Add `signum` to the set.
+    pub fn add(&mut self, signum: usize) {
+        self.0 |= 1 << signum;
+    }
+    /// Remove `signum` from the set.
+    pub fn remove(&mut self, signum: usize) {
+        self.0 &= !(1 << signum);
+    }
+    /// Whether `signum` is a member of the set.
+    pub fn contains(&self, signum: usize) -> bool {
+        self.0 & (1 << signum) != 0
+    }
+    /// Raw bitmask.
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+    /// Rebuild a set from a raw bitmask (used by `sys_sigprocmask`).
+    pub fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+    /// The lowest-numbered signal that is a member of both `self` and
+    /// `!blocked`, if any — this is the next signal eligible for delivery.
+    pub fn first_deliverable(&self, blocked: &SigSet) -> Option<usize> {
+        (1..MAX_SIG).find(|&s| self.contains(s) && !blocked.contains(s))
+    }
+}
+
+/// A user-registered handler for one signal number, installed by
+/// `sys_sigaction`.
+#[derive(Copy, Clone)]
+pub struct SigAction {
+    /// User-space entry point the trap-return path jumps to.
+    pub handler: usize,
+    /// Mask applied (OR'd into the blocked mask) while the handler runs.
+    pub mask: SigSet,
+}
+
+impl SigAction {
+    /// No handler installed: the default action applies.
+    pub fn none() -> Self {
+        Self {
+            handler: 0,
+            mask: SigSet::empty(),
+        }
+    }
+}
+
+/// Per-process table of installed handlers, indexed by signal number.
+#[derive(Clone)]
+pub struct SigActionTable {
+    actions: [SigAction; MAX_SIG],
+}
+
+impl SigActionTable {
+    /// All signals start with no handler installed (default action).
+    pub fn new() -> Self {
+        Self {
+            actions: [SigAction::none(); MAX_SIG],
+        }
+    }
+    /// Fetch the currently installed action for `signum`.
+    pub fn get(&self, signum: usize) -> SigAction {
+        self.actions[signum]
+    }
+    /// Install `action` for `signum`, returning the previous one.
+    pub fn set(&mut self, signum: usize, action: SigAction) -> SigAction {
+        core::mem::replace(&mut self.actions[signum], action)
+    }
+}
+
+/// Saved trap frame + mask, kept kernel-side in the task's `sig_context`
+/// (nothing is pushed onto the user stack) while a handler runs, so
+/// `sys_sigreturn` can restore exactly what was interrupted. Only one is
+/// held at a time: delivering another signal before `sys_sigreturn`
+/// overwrites it.
+pub struct SigContext {
+    /// The trap context active right before we redirected to the handler.
+    pub trap_cx_backup: TrapContext,
+    /// The blocked mask active right before delivery, restored on return.
+    pub saved_mask: SigSet,
+}
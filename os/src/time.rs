@@ -0,0 +1,128 @@
+//! An [`embassy_time::Driver`] backed by the RISC-V `time` CSR, so kernel
+//! tasks running on [`embassy_executor::Executor`] can `.await` delays via
+//! `embassy_time::Timer::after(...)` instead of only ever polling to
+//! completion.
+
+use crate::sbi;
+use alloc::collections::BinaryHeap;
+use core::cmp::Ordering;
+use core::task::Waker;
+use critical_section::Mutex;
+use core::cell::RefCell;
+use embassy_time_driver::Driver;
+
+/// Board timebase frequency (ticks of the `time` CSR per second), used to
+/// scale the raw CSR value into the tick rate `embassy_time` expects.
+const CLOCK_FREQ: u64 = 12_500_000;
+/// `embassy_time`'s tick rate, fixed at 1 MHz by this crate's Cargo
+/// features.
+const TICKS_PER_SEC: u64 = 1_000_000;
+
+/// A single pending wakeup: call `waker.wake()` once `now() >= deadline`.
+struct TimerEntry {
+    deadline: u64,
+    waker: Waker,
+}
+
+impl PartialEq for TimerEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+impl Eq for TimerEntry {}
+impl PartialOrd for TimerEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for TimerEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so `BinaryHeap` (max-heap) surfaces the earliest
+        // deadline first.
+        other.deadline.cmp(&self.deadline)
+    }
+}
+
+struct TimerQueue {
+    queue: BinaryHeap<TimerEntry>,
+}
+
+impl TimerQueue {
+    const fn new() -> Self {
+        Self {
+            queue: BinaryHeap::new(),
+        }
+    }
+}
+
+static TIMER_QUEUE: Mutex<RefCell<TimerQueue>> = Mutex::new(RefCell::new(TimerQueue::new()));
+
+/// Read the `time` CSR, scaled from the board's timebase frequency into
+/// `embassy_time`'s fixed tick rate.
+fn read_time_csr() -> u64 {
+    let raw: u64;
+    unsafe {
+        core::arch::asm!("rdtime {}", out(reg) raw);
+    }
+    raw * TICKS_PER_SEC / CLOCK_FREQ
+}
+
+/// Program the supervisor timer interrupt to fire at tick `at` (in CSR
+/// ticks, i.e. before the `CLOCK_FREQ`/`TICKS_PER_SEC` scaling above).
+fn set_next_timer_interrupt(at_raw_ticks: u64) {
+    sbi::set_timer(at_raw_ticks);
+}
+
+/// Insert `waker` into the global timer queue to be woken at tick
+/// `deadline`, reprogramming the supervisor timer interrupt if this is now
+/// the earliest pending deadline.
+pub fn schedule_wake(deadline: u64, waker: Waker) {
+    critical_section::with(|cs| {
+        let mut q = TIMER_QUEUE.borrow(cs).borrow_mut();
+        let is_earliest = q.queue.peek().map_or(true, |top| deadline < top.deadline);
+        q.queue.push(TimerEntry { deadline, waker });
+        if is_earliest {
+            let raw_ticks = deadline * CLOCK_FREQ / TICKS_PER_SEC;
+            set_next_timer_interrupt(raw_ticks);
+        }
+    });
+}
+
+/// Supervisor Timer Interrupt handler: pop and wake every expired entry,
+/// then reprogram (or disable) the timer for whatever's left.
+pub fn handle_timer_interrupt() {
+    critical_section::with(|cs| {
+        let mut q = TIMER_QUEUE.borrow(cs).borrow_mut();
+        let now = read_time_csr();
+        while let Some(top) = q.queue.peek() {
+            if top.deadline > now {
+                break;
+            }
+            let entry = q.queue.pop().unwrap();
+            entry.waker.wake();
+        }
+        if let Some(top) = q.queue.peek() {
+            let raw_ticks = top.deadline * CLOCK_FREQ / TICKS_PER_SEC;
+            set_next_timer_interrupt(raw_ticks);
+        } else {
+            // Empty queue: push the deadline out of reach so the timer
+            // doesn't keep firing against a stale one.
+            set_next_timer_interrupt(u64::MAX);
+        }
+    });
+    crate::task::on_timer_tick();
+}
+
+struct RiscvTimeDriver;
+
+embassy_time_driver::time_driver_impl!(static DRIVER: RiscvTimeDriver = RiscvTimeDriver);
+
+impl Driver for RiscvTimeDriver {
+    fn now(&self) -> u64 {
+        read_time_csr()
+    }
+
+    fn schedule_wake(&self, at: u64, waker: &Waker) {
+        schedule_wake(at, waker.clone());
+    }
+}
@@ -0,0 +1,118 @@
+//! Trap handling entry point: decodes `scause` and dispatches to whichever
+//! subsystem owns that trap: the Supervisor Timer Interrupt that drives
+//! [`crate::time`]'s wakeups. U-mode ecalls are taken by `user_trap.S`'s own
+//! entry for `loader`, so the `UserEnvCall` arm here does nothing.
+
+use crate::time;
+use riscv::register::{
+    scause::{self, Exception, Interrupt, Trap},
+    sie, sip,
+};
+
+/// The a0, a1, a2 and a7 registers as they stood when a U-mode `ecall` trapped into the
+/// kernel, decoded by `sret_into_user`'s assembly counterpart.
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct UserTrap {
+    /// First syscall argument / return value register.
+    pub a0: usize,
+    /// Second syscall argument register.
+    pub a1: usize,
+    /// Third syscall argument register.
+    pub a2: usize,
+    /// Syscall number.
+    pub a7: usize,
+}
+
+/// The full register file a task resumes into, saved/restored across
+/// syscalls and signal-handler delivery (`deliver_pending_signal` below,
+/// `sys_sigreturn`).
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct TrapContext {
+    /// General-purpose registers `x0`-`x31` as they stood at the trap (`x10`
+    /// is `a0`, the syscall return value / first argument register).
+    pub x: [usize; 32],
+    /// Saved `sstatus` (privilege mode to return to, interrupt-enable bits).
+    pub sstatus: usize,
+    /// Saved `sepc`: the instruction to resume at.
+    pub sepc: usize,
+}
+
+/// Acknowledge and service whatever trap `scause` reports.
+#[no_mangle]
+pub extern "C" fn trap_handler() {
+    match scause::read().cause() {
+        Trap::Interrupt(Interrupt::SupervisorTimer) => {
+            // Clear the pending bit before waking anything so a wakeup
+            // that immediately reschedules doesn't race a stale pending
+            // interrupt.
+            unsafe {
+                sip::clear_stimer();
+            }
+            time::handle_timer_interrupt();
+        }
+        Trap::Exception(Exception::UserEnvCall) => {
+            // Handled inline by `loader::enter_user`'s `sret_into_user`/
+            // `resume_after_ecall` pair for the U-mode milestone; nothing
+            // left to do here once that path owns the trap.
+        }
+        trap => {
+            panic!("Unsupported trap: {:?}", trap);
+        }
+    }
+    deliver_pending_signal();
+}
+
+/// If the current task has a deliverable (unblocked, pending) signal with a
+/// user handler installed, redirect its trap context to that handler: push
+/// the interrupted context (plus the currently-blocked mask) into
+/// `sig_context` so `sys_sigreturn` can restore it, block the handler's own
+/// mask while it runs, and rewrite `sepc`/`a0` so the next `sret` resumes
+/// directly at the handler with the signal number in `a0`. A signal with no
+/// handler installed is resolved here without ever reaching userspace:
+/// `SigDefault::Terminate` kills the task, `SigDefault::Ignore` is simply
+/// dropped.
+fn deliver_pending_signal() {
+    use crate::task::signal::{default_action, SigDefault};
+    use crate::task::{current_task, exit_current_and_run_next};
+
+    let Some(task) = current_task() else {
+        return;
+    };
+    let mut inner = task.inner_exclusive_access();
+    let Some(signum) = inner
+        .pending_signals
+        .first_deliverable(&inner.blocked_signals)
+    else {
+        return;
+    };
+    inner.pending_signals.remove(signum);
+    let action = inner.sig_actions.get(signum);
+    if action.handler == 0 {
+        drop(inner);
+        if default_action(signum) == SigDefault::Terminate {
+            exit_current_and_run_next(-(signum as i32));
+        }
+        return;
+    }
+    let saved_mask = inner.blocked_signals;
+    let trap_cx_backup = *inner.get_trap_cx();
+    inner.sig_context = Some(crate::task::signal::SigContext {
+        trap_cx_backup,
+        saved_mask,
+    });
+    inner.blocked_signals =
+        crate::task::signal::SigSet::from_bits(saved_mask.bits() | action.mask.bits());
+    let trap_cx = inner.get_trap_cx();
+    trap_cx.x[10] = signum; // a0: signal number, passed to the handler
+    trap_cx.sepc = action.handler;
+}
+
+/// Enable the Supervisor Timer Interrupt so `time::schedule_wake`'s
+/// programmed deadlines actually fire.
+pub fn enable_timer_interrupt() {
+    unsafe {
+        sie::set_stimer();
+    }
+}